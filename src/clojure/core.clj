@@ -1,9 +1,19 @@
+;;;; clojure.core;  what we define in Clojure itself,  on top of what's built in
+
 (def list (fn [& ls] ls))
 
-(defmacro defn [name args & body]
-  (list (quote def) name 
-        (list (quote fn) args 
-              (concat (list (quote do)) body))))
+;; (defn name doc-string? attr-map? [args] body..) => (def name (fn [args] (do body..)))
+;;
+;; Like def,  the metadata on our name (^:private, ^{:doc ".."}) goes on the fn we define,
+;; along with its :arglists,  and then the doc-string (as :doc) and attr-map,  if given 
+(defmacro defn [name & decl]
+  (let [doc   (if (string? (first decl)) (first decl))
+        decl  (if doc (rest decl) decl)
+        attrs (if (map? (first decl)) (first decl))
+        decl  (if attrs (rest decl) decl)
+        args  (first decl)]
+    `(def ~(with-meta name (conj {:arglists (list args)} (meta name) attrs (if doc {:doc doc})))
+       (fn ~args (do ~@(rest decl))))))
 
 (defn apply [f args]
   (lexical-eval (concat (list f) args)))
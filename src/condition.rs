@@ -0,0 +1,346 @@
+//! The condition system;  Common Lisp style handlers and restarts
+//!
+//! Signalling a condition (see `error`) doesn't immediately unwind anything.  Instead, we walk
+//! back through the handlers established with `handler-bind`, calling each whose type matches,
+//! right there at the signalling site.  A handler can decline by simply returning, or it can
+//! pick one of the restarts established with `restart-case` around the signalling site, with
+//! `invoke-restart`;  only then do we unwind, back up to that restart-case, which then
+//! evaluates the chosen restart in place of its body:
+//!
+//!   (defn div [x y]
+//!     (restart-case
+//!       (if (zero? y)
+//!         (error :divide-by-zero {:x x})
+//!         (/ x y))
+//!       (return-zero [] 0)
+//!       (return-value [r] r)))
+//!
+//!   (handler-bind
+//!     :divide-by-zero (fn [condition] (invoke-restart (quote return-value) 10))
+//!     (div 5 0)) => 10
+//!
+//! If no handler handles our condition,  whoever is embedding us (the REPL, for instance)
+//! gets a chance to choose a restart through the debugger hook
+//!
+//! Our own conditions -- dividing by zero,  an undefined symbol,  a type mismatch -- are
+//! signalled the same way (see `raise`),  so handlers and restarts work just as well on them
+//!
+//! Handlers and restarts are dynamically scoped -- they belong to whatever is running right
+//! now, not to where it was written -- so they are kept here in thread local stacks, rather
+//! than in our (lexical) Environment
+use crate::value::Value;
+use crate::ifn::IFn;
+use crate::symbol::Symbol;
+use crate::source::SourcePosition;
+use crate::persistent_list::PersistentList;
+use crate::type_tag::TypeTag;
+
+use std::cell::{Cell,RefCell};
+use std::rc::Rc;
+use std::hash::{Hash,Hasher};
+use std::fmt;
+
+/// Something gone wrong;  evaluating anything whose evaluation needs a Condition aborts,
+/// passing the Condition on up,  each form it unwinds through recorded in its trace
+#[derive(Debug,Clone)]
+pub struct Condition {
+    /// What sort of condition this is,  a keyword as given to error (:divide-by-zero);  what
+    /// handlers are matched against.  Most of our own conditions ("Undefined symbol x" and the
+    /// like) are of type nil, for now
+    pub condition_type: Rc<Value>,
+    pub message: String,
+    /// Anything else whoever signalled us wanted known about what went wrong;  a map, or nil
+    pub data: Rc<Value>,
+    /// The condition that led to this one, if any
+    pub cause: Option<Rc<Condition>>,
+    /// The calls we were evaluating as we unwound through them,  innermost first
+    pub trace: Vec<TraceFrame>,
+    /// Where in our source we went wrong,  when that's not the position of a form in our
+    /// trace;  say, where the reader gave up 
+    pub position: Option<SourcePosition>
+}
+impl Condition {
+    pub fn new(message: String) -> Condition {
+	Condition {
+	    condition_type: Rc::new(Value::Nil),
+	    message,
+	    data: Rc::new(Value::Nil),
+	    cause: None,
+	    trace: vec![],
+	    position: None
+	}
+    }
+    /// Whether (catch catch_type e ..) catches us;  catch_type is either the keyword type given
+    /// to error,  or a class name:  the name of our type tag or one of Clojure's catch-alls,
+    /// which catch anything,  or ExceptionInfo,  which (as in Clojure) catches only what
+    /// carries data 
+    pub fn is_caught_by(&self,catch_type: &Rc<Value>) -> bool {
+	if *catch_type == self.condition_type {
+	    return true;
+	}
+	let class = match &**catch_type {
+	    Value::Symbol(sym) => sym,
+	    _ => return false
+	};
+	// Class names are read as plain symbols;  clojure.lang.ExceptionInfo has no namespace,  it's
+	// all name,  so foo/Exception is no class of ours 
+	let is_class = |names: &[&str]| names.iter().any(|name| *class == Symbol::intern(name));
+	if is_class(&["Condition",&TypeTag::Condition.to_string(),"Exception","java.lang.Exception","Throwable","java.lang.Throwable"]) {
+	    return true;
+	}
+	is_class(&["ExceptionInfo","clojure.lang.ExceptionInfo"]) && *self.data != Value::Nil
+    }
+    /// This condition, having now unwound through the call form 
+    pub fn traced_through(&self,form: &Rc<Value>) -> Condition {
+	let mut condition = self.clone();
+	condition.trace.push(TraceFrame::of_call(form));
+	condition
+    }
+    /// Our multi-line description of this condition,  its data, where it came from,  and
+    /// what caused it.  Calls read from a source say where they were,  and the first of them
+    /// (or our own position,  if we have one) quotes that line of source
+    ///
+    ///   #Condition[":divide-by-zero {:x 5}"]
+    ///     type: :divide-by-zero
+    ///     data: {:x 5}
+    ///     at error src/math.clj:3:5
+    ///             (error :divide-by-zero {:x x}))
+    ///             ^
+    ///     at div src/math.clj:7:3
+    ///     at f
+    ///   Caused by #Condition["..."]
+    ///     ..
+    pub fn report(&self) -> String {
+	let mut lines = vec![self.to_string()];
+	if *self.condition_type != Value::Nil {
+	    lines.push(format!("  type: {}",self.condition_type.to_string_explicit()));
+	}
+	if *self.data != Value::Nil {
+	    lines.push(format!("  data: {}",self.data.to_string_explicit()));
+	}
+	let mut excerpted = false;
+	let mut push_excerpt = |lines: &mut Vec<String>,position: &SourcePosition| {
+	    if !excerpted {
+		excerpted = true;
+		if let Some(excerpt) = position.excerpt() {
+		    lines.extend(excerpt.lines().map(|line| format!("      {}",line)));
+		}
+	    }
+	};
+	if let Some(position) = &self.position {
+	    lines.push(format!("  at {}",position));
+	    push_excerpt(&mut lines,position);
+	}
+	for frame in self.trace.iter() {
+	    lines.push(format!("  at {}",frame));
+	    if let Some(position) = &frame.position {
+		push_excerpt(&mut lines,position);
+	    }
+	}
+	if let Some(cause) = &self.cause {
+	    lines.push(format!("Caused by {}",cause.report()));
+	}
+	lines.join("\n")
+    }
+}
+// A condition is what went wrong;  where we happened to notice it along the way doesn't
+// make two the same condition any less so 
+impl PartialEq for Condition {
+    fn eq(&self, other: &Condition) -> bool {
+	self.condition_type == other.condition_type
+	    && self.message == other.message
+	    && self.data == other.data
+	    && self.cause == other.cause
+    }
+}
+impl Hash for Condition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+	self.condition_type.hash(state);
+	self.message.hash(state);
+	self.data.hash(state);
+    }
+}
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	write!(f, "#Condition[\"{}\"]",self.message)
+    }
+}
+
+/// A call a condition unwound through;  the name of the fn called,  and where the call was,
+/// if it was read from a source 
+#[derive(Debug,Clone)]
+pub struct TraceFrame {
+    pub name: String,
+    pub position: Option<SourcePosition>,
+    // The call itself;  so we know not to trace the same call twice as we unwind through it 
+    pub form: Rc<Value>
+}
+impl TraceFrame {
+    /// The frame for the call (f args);  named f,  or just fn when what's called isn't named
+    pub fn of_call(form: &Rc<Value>) -> TraceFrame {
+	let name = match &**form {
+	    Value::PersistentList(PersistentList::Cons(head,..)) => match &**head {
+		Value::Symbol(sym) => sym.to_string(),
+		_ => String::from("fn")
+	    },
+	    _ => form.to_string()
+	};
+	TraceFrame {
+	    name,
+	    position: SourcePosition::of_form(form),
+	    form: Rc::clone(form)
+	}
+    }
+}
+impl fmt::Display for TraceFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	match &self.position {
+	    Some(position) => write!(f,"{} {}",self.name,position),
+	    None => write!(f,"{}",self.name)
+	}
+    }
+}
+
+/// A handler established by handler-bind,  for conditions of condition_type,  or by one of a
+/// try's catch clauses
+#[derive(Debug,Clone)]
+pub struct Handler {
+    pub condition_type: Rc<Value>,
+    /// None for a catch clause,  which handles its conditions by letting them unwind back out
+    /// to its try,  where the catch itself is run 
+    pub handler_fn: Option<Rc<dyn IFn>>
+}
+/// A restart established by restart-case
+#[derive(Debug,Clone)]
+pub struct Restart {
+    /// Which restart-case established this restart, and so where invoking it unwinds to
+    pub restart_case_id: usize,
+    pub name: String,
+    pub params: Vec<Symbol>
+}
+/// A transfer of control to a restart,  on its way up to the restart-case that established it
+#[derive(Debug,Clone,PartialEq,Hash)]
+pub struct RestartInvocation {
+    pub restart_case_id: usize,
+    pub name: String,
+    pub args: Vec<Rc<Value>>
+}
+impl Restart {
+    pub fn invocation(&self,args: Vec<Rc<Value>>) -> RestartInvocation {
+	RestartInvocation {
+	    restart_case_id: self.restart_case_id,
+	    name: self.name.clone(),
+	    args
+	}
+    }
+}
+
+/// Given an unhandled condition and the restarts available,  lets our embedder choose a
+/// restart to invoke;  returning None means no restart,  and the condition is returned as is
+pub type DebuggerHook = Rc<dyn Fn(&Condition,&[Restart]) -> Option<RestartInvocation>>;
+
+thread_local! {
+    static HANDLERS: RefCell<Vec<Handler>> = const { RefCell::new(vec![]) };
+    static RESTARTS: RefCell<Vec<Restart>> = const { RefCell::new(vec![]) };
+    static NEXT_RESTART_CASE_ID: Cell<usize> = const { Cell::new(0) };
+    static DEBUGGER_HOOK: RefCell<Option<DebuggerHook>> = RefCell::new(None);
+}
+
+/// Restarts and invocations are named by symbol;  (invoke-restart (quote return-zero))
+pub fn restart_name(name: &Value) -> Option<String> {
+    match name {
+	Value::Symbol(sym) => Some(sym.name.clone()),
+	_ => None
+    }
+}
+
+/// Returns the depth to pop back down to once they're out of scope
+pub fn push_handlers(handlers: Vec<Handler>) -> usize {
+    HANDLERS.with(|stack| {
+	let mut stack = stack.borrow_mut();
+	let depth = stack.len();
+	stack.extend(handlers);
+	depth
+    })
+}
+/// Pops handlers back down to a depth of `depth`
+pub fn pop_handlers(depth: usize) {
+    HANDLERS.with(|stack| stack.borrow_mut().truncate(depth));
+}
+
+pub fn new_restart_case_id() -> usize {
+    NEXT_RESTART_CASE_ID.with(|id| {
+	let next_id = id.get();
+	id.set(next_id + 1);
+	next_id
+    })
+}
+/// Returns the depth to pop back down to once they're out of scope
+pub fn push_restarts(restarts: Vec<Restart>) -> usize {
+    RESTARTS.with(|stack| {
+	let mut stack = stack.borrow_mut();
+	let depth = stack.len();
+	stack.extend(restarts);
+	depth
+    })
+}
+/// Pops restarts back down to a depth of `depth`
+pub fn pop_restarts(depth: usize) {
+    RESTARTS.with(|stack| stack.borrow_mut().truncate(depth));
+}
+/// The restarts currently available,  innermost last
+pub fn active_restarts() -> Vec<Restart> {
+    RESTARTS.with(|stack| stack.borrow().clone())
+}
+/// Finds the innermost active restart with this name
+pub fn find_restart(name: &str) -> Option<Restart> {
+    RESTARTS.with(|stack| {
+	stack.borrow().iter().rev().find(|restart| restart.name == name).cloned()
+    })
+}
+
+pub fn set_debugger_hook(hook: Option<DebuggerHook>) {
+    DEBUGGER_HOOK.with(|debugger_hook| *debugger_hook.borrow_mut() = hook);
+}
+
+/// Signals condition to our handlers for its type, innermost first
+///
+/// Returns what the signalling form should evaluate to;  a Value::RestartInvocation if a handler
+/// (or failing that, the debugger hook) chose a restart,  or otherwise the condition itself,
+/// unwinding 
+pub fn signal(condition: Condition) -> Value {
+    let handlers = HANDLERS.with(|stack| stack.borrow().clone());
+    // Handlers are given the condition to look at, not to unwind from 
+    let held_condition = Value::HeldCondition(condition.clone());
+    for (depth,handler) in handlers.iter().enumerate().rev() {
+	let handler_fn = match &handler.handler_fn {
+	    Some(handler_fn) if handler.condition_type == condition.condition_type => handler_fn,
+	    // A catch clause;  we unwind to it,  past any handler or debugger hook further out 
+	    None if condition.is_caught_by(&handler.condition_type) => return Value::Condition(condition),
+	    _ => continue
+	};
+	// A handler runs with only the handlers outside of it still active,  so signalling
+	// inside a handler doesn't land right back in that same handler
+	let inner_handlers = HANDLERS.with(|stack| stack.borrow_mut().split_off(depth));
+	let handler_result = handler_fn.invoke(vec![&held_condition]);
+	HANDLERS.with(|stack| stack.borrow_mut().extend(inner_handlers));
+
+	if let Value::RestartInvocation(_) = handler_result {
+	    return handler_result;
+	}
+	// Otherwise our handler declined,  on to the next
+    }
+
+    let debugger_hook = DEBUGGER_HOOK.with(|hook| hook.borrow().clone());
+    if let Some(debugger_hook) = debugger_hook {
+	if let Some(invocation) = debugger_hook(&condition,&active_restarts()) {
+	    return Value::RestartInvocation(Rc::new(invocation));
+	}
+    }
+    Value::Condition(condition)
+}
+/// Signals a plain condition (of type nil) saying message;  how our own fns and special forms
+/// give up when something goes wrong ("Undefined symbol x",  "Wrong number of arguments ..")
+pub fn raise(message: String) -> Value {
+    signal(Condition::new(message))
+}
@@ -0,0 +1,122 @@
+//! Data readers;  what the reader makes of a tagged literal,  #tag form
+//!
+//! A tagged literal is read by reading its form,  and handing it to the data reader for its
+//! tag,  whose result is what we read:
+//!
+//!   #inst "2020-01-01T00:00:00Z"  => an Inst
+//!   #uuid "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"  => a Uuid
+//!
+//! We look for a tag's data reader,  in order,  in
+//!   1. *data-readers*,  a map of tag symbols to ClojureRS fns;  (def *data-readers* {'my/point ->point})
+//!   2. our registry of built in readers,  #inst and #uuid,  and any more whoever is embedding
+//!      us registers with register_data_reader
+//!   3. *default-data-reader-fn*,  given both the tag and the form,  for tags with no reader
+//!      of their own
+//!
+//! A data reader runs once for each tagged literal we read,  as we read it,  just as in
+//! Clojure;  a LispReader doesn't read a form until all of it could be there,  so nothing is
+//! read again because its last line hadn't come in yet
+//!
+//! The reader doesn't have an environment of its own to look up *data-readers* in;  it uses
+//! the one set with set_data_reader_environment,  our clojure.core environment
+use crate::value::{Value,ToValue};
+use crate::environment::Environment;
+use crate::condition::Condition;
+use crate::symbol::Symbol;
+use crate::inst::Inst;
+use crate::uuid::Uuid;
+use crate::persistent_list_map::IPersistentListMap;
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::rc::{Rc,Weak};
+
+/// Turns the form of a tagged literal into the value it stands for,  or a Condition if it
+/// can't
+#[derive(Clone)]
+pub enum DataReader {
+    Native(fn(&Value) -> Value),
+    /// A ClojureRS fn (or anything else we can invoke)
+    Fn(Rc<Value>)
+}
+impl DataReader {
+    pub fn read(&self,form: &Value) -> Value {
+	match self {
+	    DataReader::Native(read) => read(form),
+	    DataReader::Fn(ifn) => invoke(ifn,vec![form])
+	}
+    }
+}
+fn invoke(ifn: &Value,args: Vec<&Value>) -> Value {
+    match ifn.as_ifn() {
+	Some(ifn) => ifn.invoke(args),
+	None => Value::Condition(Condition::new(format!("Data reader is not a function: {}",ifn)))
+    }
+}
+
+thread_local! {
+    static DATA_READERS: RefCell<HashMap<Symbol,DataReader>> = RefCell::new(default_data_readers());
+    // Where we look up *data-readers* and *default-data-reader-fn*;  weak,  as our
+    // environment is what holds on to us,  not the other way around
+    static DATA_READER_ENVIRONMENT: RefCell<Weak<Environment>> = const { RefCell::new(Weak::new()) };
+}
+fn default_data_readers() -> HashMap<Symbol,DataReader> {
+    let mut data_readers = HashMap::new();
+    data_readers.insert(Symbol::intern("inst"),DataReader::Native(read_inst));
+    data_readers.insert(Symbol::intern("uuid"),DataReader::Native(read_uuid));
+    data_readers
+}
+/// From here on,  #tag form is read with reader (unless *data-readers* has one for tag)
+// For whoever is embedding us 
+#[allow(dead_code)]
+pub fn register_data_reader(tag: Symbol,reader: DataReader) {
+    DATA_READERS.with(|data_readers| data_readers.borrow_mut().insert(tag,reader));
+}
+/// Sets the environment whose *data-readers* and *default-data-reader-fn* the reader uses
+pub fn set_data_reader_environment(environment: &Rc<Environment>) {
+    DATA_READER_ENVIRONMENT.with(|data_reader_environment| *data_reader_environment.borrow_mut() = Rc::downgrade(environment));
+}
+
+/// The value of the tagged literal #tag form,  or None if nothing reads tag.  A data reader
+/// that fails gives back its Condition
+pub fn read_tagged(tag: &Symbol,form: &Value) -> Option<Value> {
+    let environment = DATA_READER_ENVIRONMENT.with(|data_reader_environment| data_reader_environment.borrow().upgrade());
+    if let Some(environment) = &environment {
+	if let Some(Value::PersistentListMap(data_readers)) = environment.try_get(&Symbol::intern("*data-readers*")).as_deref() {
+	    let reader = data_readers.get(&tag.to_rc_value());
+	    if *reader != Value::Nil {
+		return Some(DataReader::Fn(reader).read(form));
+	    }
+	}
+    }
+    if let Some(reader) = DATA_READERS.with(|data_readers| data_readers.borrow().get(tag).cloned()) {
+	return Some(reader.read(form));
+    }
+    if let Some(environment) = &environment {
+	if let Some(default_reader) = environment.try_get(&Symbol::intern("*default-data-reader-fn*")).filter(|reader| reader.as_ifn().is_some()) {
+	    return Some(invoke(&default_reader,vec![&tag.to_value(),form]));
+	}
+    }
+    None
+}
+
+/// #inst "2020-01-01T00:00:00Z"
+fn read_inst(form: &Value) -> Value {
+    match form {
+	Value::String(timestamp) => match Inst::parse(timestamp) {
+	    Some(inst) => Value::Inst(inst),
+	    None => Value::Condition(Condition::new(format!("Unrecognized date/time syntax: {}",timestamp)))
+	},
+	_ => Value::Condition(Condition::new(format!("Type mismatch; Expected instance of rust.std.string.String, Recieved type {}",form.type_tag())))
+    }
+}
+/// #uuid "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"
+fn read_uuid(form: &Value) -> Value {
+    match form {
+	Value::String(uuid) => match Uuid::parse(uuid) {
+	    Some(uuid) => Value::Uuid(uuid),
+	    None => Value::Condition(Condition::new(format!("Invalid UUID string: {}",uuid)))
+	},
+	_ => Value::Condition(Condition::new(format!("Type mismatch; Expected instance of rust.std.string.String, Recieved type {}",form.type_tag())))
+    }
+}
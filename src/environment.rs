@@ -2,7 +2,10 @@ use crate::value::{Value,ToValue};
 use crate::namespace::{Namespace,Namespaces};
 use crate::Symbol;
 use crate::rust_core;
-use crate::rust_core::{AddFn,StrFn};
+use crate::condition;
+use crate::persistent_list_map::PersistentListMap;
+use crate::data_readers;
+use crate::reader;
 
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -59,15 +62,76 @@ impl Environment {
 	    }
 	}
     }
+    /// Maps sym to val in the namespace named ns,  rather than in ours 
+    pub fn insert_into_namespace(&self,ns: &Symbol,sym: Symbol,val: Rc<Value>)
+    {
+	match self {
+	    MainEnvironment(EnvironmentVal {curr_ns,namespaces}) => {
+		if *ns == curr_ns.name {
+		    curr_ns.insert(sym,val);
+		}
+		else {
+		    namespaces.insert_into(ns,sym,val);
+		}
+	    },
+	    LocalEnvironment(parent_env,_) => parent_env.insert_into_namespace(ns,sym,val)
+	}
+    }
+    /// From here on, in our namespace,  alias/name means ns/name
+    pub fn add_alias(&self,alias: Symbol,ns: Symbol)
+    {
+	match self {
+	    MainEnvironment(EnvironmentVal {curr_ns,..}) => curr_ns.add_alias(alias,ns),
+	    LocalEnvironment(parent_env,_) => parent_env.add_alias(alias,ns)
+	}
+    }
+    /// The namespace ns names here;  the one we call ns (see add_alias),  if we call any
+    /// that,  or else ns itself,  if there's a namespace by that name 
+    pub fn resolve_namespace(&self,ns: &Symbol) -> Option<Symbol>
+    {
+	match self {
+	    MainEnvironment(EnvironmentVal {curr_ns,namespaces}) => {
+		curr_ns.resolve_alias(ns).or_else(|| (*ns == curr_ns.name || namespaces.contains(ns)).then(|| ns.clone()))
+	    },
+	    LocalEnvironment(parent_env,_) => parent_env.resolve_namespace(ns)
+	}
+    }
+    /// What sym means here;  signals "Undefined symbol sym" if it means nothing 
     pub fn get(&self, sym: &Symbol) -> Rc<Value> 
+    {
+	self.lookup(sym).unwrap_or_else(|message| Rc::new(condition::raise(message)))
+    }
+    /// What sym means here,  if anything;  for when it meaning nothing is no error 
+    pub fn try_get(&self, sym: &Symbol) -> Option<Rc<Value>> 
+    {
+	self.lookup(sym).ok()
+    }
+    // What sym means here,  or else why it doesn't mean anything 
+    fn lookup(&self, sym: &Symbol) -> Result<Rc<Value>,String> 
     {
 	match self {
-	    MainEnvironment(EnvironmentVal {curr_ns,..}) => curr_ns.get(sym),
+	    MainEnvironment(EnvironmentVal {curr_ns,namespaces}) => {
+		let ns = match &sym.ns {
+		    Some(ns) => Symbol::intern(ns),
+		    None => return curr_ns.try_get(sym).ok_or_else(|| format!("Undefined symbol {}",sym))
+		};
+		// user/x,  in the namespace user,  is just x 
+		if ns == curr_ns.name {
+		    return curr_ns.try_get(&sym.unqualified()).ok_or_else(|| format!("Undefined symbol {}",sym));
+		}
+		// set/union,  having aliased clojure.set as set,  is clojure.set/union 
+		let ns = curr_ns.resolve_alias(&ns).unwrap_or(ns);
+		match namespaces.try_get_from(&ns,&sym.unqualified()) {
+		    Some(Some(val)) => Ok(val),
+		    Some(None) => Err(format!("Undefined symbol {}",sym)),
+		    None => Err(format!("No such namespace: {}",ns))
+		}
+	    },
 	    
 	    LocalEnvironment(parent_env,mappings) => {
 		match mappings.borrow().get(sym) {
-		    Some(val) => Rc::clone(val),
-		    None => parent_env.get(sym) 
+		    Some(val) => Ok(Rc::clone(val)),
+		    None => parent_env.lookup(sym) 
 		}
 	    }
 	}
@@ -75,26 +139,194 @@ impl Environment {
     pub fn clojure_core_environment() -> Rc<Environment> {
 	// Register our macros / functions ahead of time
 	let add_fn = rust_core::AddFn{};
+	let subtract_fn = rust_core::SubtractFn{};
+	let multiply_fn = rust_core::MultiplyFn{};
+	let divide_fn = rust_core::DivideFn{};
+	let quot_fn = rust_core::QuotFn{};
+	let rem_fn = rust_core::RemFn{};
+	let mod_fn = rust_core::ModFn{};
+	let inc_fn = rust_core::IncFn{};
+	let dec_fn = rust_core::DecFn{};
+	let add_promoting_fn = rust_core::AddPromotingFn{};
+	let subtract_promoting_fn = rust_core::SubtractPromotingFn{};
+	let multiply_promoting_fn = rust_core::MultiplyPromotingFn{};
+	let inc_promoting_fn = rust_core::IncPromotingFn{};
+	let dec_promoting_fn = rust_core::DecPromotingFn{};
+	let max_fn = rust_core::MaxFn{};
+	let min_fn = rust_core::MinFn{};
+	let equals_fn = rust_core::EqualsFn{};
+	let num_equals_fn = rust_core::NumEqualsFn{};
+	let not_equals_fn = rust_core::NotEqualsFn{};
+	let less_than_fn = rust_core::LessThanFn{};
+	let less_than_or_equal_fn = rust_core::LessThanOrEqualFn{};
+	let greater_than_fn = rust_core::GreaterThanFn{};
+	let greater_than_or_equal_fn = rust_core::GreaterThanOrEqualFn{};
+	let is_zero_fn = rust_core::IsZeroFn{};
+	let is_pos_fn = rust_core::IsPosFn{};
+	let is_neg_fn = rust_core::IsNegFn{};
+	let numerator_fn = rust_core::NumeratorFn{};
+	let denominator_fn = rust_core::DenominatorFn{};
+	let bigint_fn = rust_core::BigIntFn{};
+	let rationalize_fn = rust_core::RationalizeFn{};
 	let str_fn = rust_core::StrFn{};
+	let nth_fn = rust_core::NthFn{};
+	let concat_fn = rust_core::ConcatFn{};
+	let first_fn = rust_core::FirstFn{};
+	let rest_fn = rust_core::RestFn{};
+	let count_fn = rust_core::CountFn{};
+	let vec_fn = rust_core::VecFn{};
+	let set_fn = rust_core::SetFn{};
+	let hash_set_fn = rust_core::HashSetFn{};
+	let conj_fn = rust_core::ConjFn{};
+	let disj_fn = rust_core::DisjFn{};
+	let contains_fn = rust_core::ContainsFn{};
+	let meta_fn = rust_core::MetaFn{};
+	let with_meta_fn = rust_core::WithMetaFn{};
+	let vary_meta_fn = rust_core::VaryMetaFn{};
+	let hash_fn = rust_core::HashFn{};
+	let read_string_fn = rust_core::ReadStringFn{};
+	let read_fn = rust_core::ReadFn{};
+	let union_fn = rust_core::UnionFn{};
+	let intersection_fn = rust_core::IntersectionFn{};
+	let difference_fn = rust_core::DifferenceFn{};
+	let select_fn = rust_core::SelectFn{};
+	let project_fn = rust_core::ProjectFn{};
+	let rename_keys_fn = rust_core::RenameKeysFn{};
+	let index_fn = rust_core::IndexFn{};
+	let char_fn = rust_core::CharFn{};
+	let int_fn = rust_core::IntFn{};
+	let is_char_fn = rust_core::IsCharFn{};
+	let is_string_fn = rust_core::IsStringFn{};
+	let is_map_fn = rust_core::IsMapFn{};
+	let print_string_fn = rust_core::PrintStringFn{};
+	let error_fn = rust_core::ErrorFn{};
+	let invoke_restart_fn = rust_core::InvokeRestartFn{};
+	let ex_info_fn = rust_core::ExInfoFn{};
+	let ex_data_fn = rust_core::ExDataFn{};
+	let ex_message_fn = rust_core::ExMessageFn{};
+	let ex_cause_fn = rust_core::ExCauseFn{};
+	let inst_ms_fn = rust_core::InstMsFn{};
+	// Hardcoded fns
+	let lexical_eval_fn = Value::LexicalEvalFn{};
 	// Hardcoded macros
 	let let_macro = Value::LetMacro{};
 	let quote_macro = Value::QuoteMacro{};
 	let def_macro = Value::DefMacro{};
 	let fn_macro = Value::FnMacro{};
 	let defmacro_macro = Value::DefmacroMacro{};
+	let if_macro = Value::IfMacro{};
+	let do_macro = Value::DoMacro{};
+	let loop_macro = Value::LoopMacro{};
+	let recur_macro = Value::RecurMacro{};
+	let handler_bind_macro = Value::HandlerBindMacro{};
+	let restart_case_macro = Value::RestartCaseMacro{};
+	let try_macro = Value::TryMacro{};
+	let throw_macro = Value::ThrowMacro{};
 	
-	let mut environment = Rc::new(Environment::new_main_environment());
+	let environment = Rc::new(Environment::new_main_environment());
 	
 	let eval_fn = rust_core::EvalFn::new(Rc::clone(&environment));
+	let alias_fn = rust_core::AliasFn::new(Rc::clone(&environment));
 
 	environment.insert(Symbol::intern("+"),add_fn.to_rc_value());
+	environment.insert(Symbol::intern("-"),subtract_fn.to_rc_value());
+	environment.insert(Symbol::intern("*"),multiply_fn.to_rc_value());
+	environment.insert(Symbol::intern("/"),divide_fn.to_rc_value());
+	environment.insert(Symbol::intern("quot"),quot_fn.to_rc_value());
+	environment.insert(Symbol::intern("rem"),rem_fn.to_rc_value());
+	environment.insert(Symbol::intern("mod"),mod_fn.to_rc_value());
+	environment.insert(Symbol::intern("inc"),inc_fn.to_rc_value());
+	environment.insert(Symbol::intern("dec"),dec_fn.to_rc_value());
+	environment.insert(Symbol::intern("+'"),add_promoting_fn.to_rc_value());
+	environment.insert(Symbol::intern("-'"),subtract_promoting_fn.to_rc_value());
+	environment.insert(Symbol::intern("*'"),multiply_promoting_fn.to_rc_value());
+	environment.insert(Symbol::intern("inc'"),inc_promoting_fn.to_rc_value());
+	environment.insert(Symbol::intern("dec'"),dec_promoting_fn.to_rc_value());
+	environment.insert(Symbol::intern("max"),max_fn.to_rc_value());
+	environment.insert(Symbol::intern("min"),min_fn.to_rc_value());
+	environment.insert(Symbol::intern("="),equals_fn.to_rc_value());
+	environment.insert(Symbol::intern("=="),num_equals_fn.to_rc_value());
+	environment.insert(Symbol::intern("not="),not_equals_fn.to_rc_value());
+	environment.insert(Symbol::intern("<"),less_than_fn.to_rc_value());
+	environment.insert(Symbol::intern("<="),less_than_or_equal_fn.to_rc_value());
+	environment.insert(Symbol::intern(">"),greater_than_fn.to_rc_value());
+	environment.insert(Symbol::intern(">="),greater_than_or_equal_fn.to_rc_value());
+	environment.insert(Symbol::intern("zero?"),is_zero_fn.to_rc_value());
+	environment.insert(Symbol::intern("pos?"),is_pos_fn.to_rc_value());
+	environment.insert(Symbol::intern("neg?"),is_neg_fn.to_rc_value());
+	environment.insert(Symbol::intern("numerator"),numerator_fn.to_rc_value());
+	environment.insert(Symbol::intern("denominator"),denominator_fn.to_rc_value());
+	environment.insert(Symbol::intern("bigint"),bigint_fn.to_rc_value());
+	environment.insert(Symbol::intern("rationalize"),rationalize_fn.to_rc_value());
 	environment.insert(Symbol::intern("let"),let_macro.to_rc_value());
 	environment.insert(Symbol::intern("str"),str_fn.to_rc_value());
 	environment.insert(Symbol::intern("quote"),quote_macro.to_rc_value());
+	environment.insert(Symbol::intern("do"),do_macro.to_rc_value());
 	environment.insert(Symbol::intern("def"),def_macro.to_rc_value());
 	environment.insert(Symbol::intern("fn"),fn_macro.to_rc_value());
 	environment.insert(Symbol::intern("defmacro"),defmacro_macro.to_rc_value());
+	environment.insert(Symbol::intern("if"),if_macro.to_rc_value());
+	environment.insert(Symbol::intern("loop"),loop_macro.to_rc_value());
+	environment.insert(Symbol::intern("recur"),recur_macro.to_rc_value());
 	environment.insert(Symbol::intern("eval"),eval_fn.to_rc_value());
+	environment.insert(Symbol::intern("lexical-eval"),lexical_eval_fn.to_rc_value());
+	environment.insert(Symbol::intern("nth"),nth_fn.to_rc_value());
+	environment.insert(Symbol::intern("concat"),concat_fn.to_rc_value());
+	environment.insert(Symbol::intern("first"),first_fn.to_rc_value());
+	environment.insert(Symbol::intern("rest"),rest_fn.to_rc_value());
+	environment.insert(Symbol::intern("count"),count_fn.to_rc_value());
+	environment.insert(Symbol::intern("vec"),vec_fn.to_rc_value());
+	environment.insert(Symbol::intern("set"),set_fn.to_rc_value());
+	environment.insert(Symbol::intern("hash-set"),hash_set_fn.to_rc_value());
+	environment.insert(Symbol::intern("conj"),conj_fn.to_rc_value());
+	environment.insert(Symbol::intern("disj"),disj_fn.to_rc_value());
+	environment.insert(Symbol::intern("contains?"),contains_fn.to_rc_value());
+	environment.insert(Symbol::intern("meta"),meta_fn.to_rc_value());
+	environment.insert(Symbol::intern("with-meta"),with_meta_fn.to_rc_value());
+	environment.insert(Symbol::intern("vary-meta"),vary_meta_fn.to_rc_value());
+	environment.insert(Symbol::intern("hash"),hash_fn.to_rc_value());
+	environment.insert(Symbol::intern("read-string"),read_string_fn.to_rc_value());
+	environment.insert(Symbol::intern("read"),read_fn.to_rc_value());
+	environment.insert(Symbol::intern("alias"),alias_fn.to_rc_value());
+	// Our builtins live in user for now,  but syntax-quote expands into calls to these,
+	// qualified so they mean the same thing wherever the expansion lands 
+	let clojure_core = Symbol::intern("clojure.core");
+	environment.insert_into_namespace(&clojure_core,Symbol::intern("concat"),concat_fn.to_rc_value());
+	environment.insert_into_namespace(&clojure_core,Symbol::intern("vec"),vec_fn.to_rc_value());
+	environment.insert_into_namespace(&clojure_core,Symbol::intern("set"),set_fn.to_rc_value());
+	let clojure_set = Symbol::intern("clojure.set");
+	environment.insert_into_namespace(&clojure_set,Symbol::intern("union"),union_fn.to_rc_value());
+	environment.insert_into_namespace(&clojure_set,Symbol::intern("intersection"),intersection_fn.to_rc_value());
+	environment.insert_into_namespace(&clojure_set,Symbol::intern("difference"),difference_fn.to_rc_value());
+	environment.insert_into_namespace(&clojure_set,Symbol::intern("select"),select_fn.to_rc_value());
+	environment.insert_into_namespace(&clojure_set,Symbol::intern("project"),project_fn.to_rc_value());
+	environment.insert_into_namespace(&clojure_set,Symbol::intern("rename-keys"),rename_keys_fn.to_rc_value());
+	environment.insert_into_namespace(&clojure_set,Symbol::intern("index"),index_fn.to_rc_value());
+	environment.insert(Symbol::intern("char"),char_fn.to_rc_value());
+	environment.insert(Symbol::intern("int"),int_fn.to_rc_value());
+	environment.insert(Symbol::intern("char?"),is_char_fn.to_rc_value());
+	environment.insert(Symbol::intern("string?"),is_string_fn.to_rc_value());
+	environment.insert(Symbol::intern("map?"),is_map_fn.to_rc_value());
+	environment.insert(Symbol::intern("print-string"),print_string_fn.to_rc_value());
+	environment.insert(Symbol::intern("handler-bind"),handler_bind_macro.to_rc_value());
+	environment.insert(Symbol::intern("restart-case"),restart_case_macro.to_rc_value());
+	environment.insert(Symbol::intern("try"),try_macro.to_rc_value());
+	environment.insert(Symbol::intern("throw"),throw_macro.to_rc_value());
+	environment.insert(Symbol::intern("error"),error_fn.to_rc_value());
+	environment.insert(Symbol::intern("invoke-restart"),invoke_restart_fn.to_rc_value());
+	environment.insert(Symbol::intern("ex-info"),ex_info_fn.to_rc_value());
+	environment.insert(Symbol::intern("ex-data"),ex_data_fn.to_rc_value());
+	environment.insert(Symbol::intern("ex-message"),ex_message_fn.to_rc_value());
+	environment.insert(Symbol::intern("ex-cause"),ex_cause_fn.to_rc_value());
+	environment.insert(Symbol::intern("inst-ms"),inst_ms_fn.to_rc_value());
+	// Readers for tagged literals on top of #inst and #uuid,  and one for tags with none;
+	// (def *data-readers* {'my/point ->point})
+	environment.insert(Symbol::intern("*data-readers*"),PersistentListMap::Empty(None).to_rc_value());
+	environment.insert(Symbol::intern("*default-data-reader-fn*"),Value::Nil.to_rc_value());
+	// Which the reader,  having no environment of its own,  looks up in ours;  as it does
+	// the aliases ::alias/name keywords use 
+	data_readers::set_data_reader_environment(&environment);
+	reader::set_reader_environment(&environment);
 
 	environment
     }
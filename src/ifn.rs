@@ -11,11 +11,14 @@
 //!    ({:name "Blah" :age 20} :name)
 //! As well as a few more types. 
 use crate::value::Value;
+use crate::environment::Environment;
+use crate::symbol::Symbol;
+use crate::persistent_list_map::PersistentListMap;
 
 use dyn_clone::DynClone;
 
 use std::fmt::Debug;
-use std::hash::Hash;
+use std::rc::Rc;
 
 //
 // Based on: clojure.lang.IFn 
@@ -38,5 +41,40 @@ use std::hash::Hash;
  
 pub trait IFn : Debug + DynClone {
     fn invoke(&self,args: Vec<&Value>) -> Value;
+    /// Invokes our IFn,  but lets it hand back whatever it has left to evaluate in tail position
+    /// instead of evaluating it itself;  this is what lets our eval loop run tail calls without
+    /// growing the Rust stack.  Most IFns (anything implemented in Rust) just finish the job 
+    fn invoke_tail(&self,args: Vec<&Value>) -> Invocation {
+	Invocation::Done(Rc::new(self.invoke(args)))
+    }
+    /// Our metadata;  only a fn given some with with-meta (see meta.rs) has any 
+    fn meta(&self) -> Option<Rc<PersistentListMap>> {
+	None
+    }
 }
 dyn_clone::clone_trait_object!(IFn);
+
+/// The result of invoking something function-like,  when whoever invoked it is willing
+/// to finish evaluating it themselves 
+#[derive(Debug,Clone)]
+pub enum Invocation {
+    /// Evaluation is finished; here's the result 
+    Done(Rc<Value>),
+    /// Evaluation still needs to evaluate this form in this environment, in tail position 
+    TailCall(Rc<Value>,Rc<Environment>),
+    /// Like a TailCall into the body of a fn or loop,  whose params are already bound in this
+    /// environment;  any recur in that body now jumps back here 
+    Enter(Rc<RecursionPoint>,Rc<Environment>),
+    /// Evaluation should jump back to the nearest RecursionPoint,  rebinding its params to these values 
+    Recur(Vec<Rc<Value>>)
+}
+
+/// Somewhere a recur can jump back to;  a fn or loop, whose params are rebound to the
+/// arguments of recur before its body is evaluated again 
+#[derive(Debug,Clone)]
+pub struct RecursionPoint {
+    pub params: Vec<Symbol>,
+    pub body: Rc<Value>,
+    /// What our params are bound on top of each time around 
+    pub environment: Rc<Environment>
+}
@@ -0,0 +1,184 @@
+//! Instants in time;  #inst "2020-01-01T12:00:00.000-00:00"
+//!
+//! We keep an instant as milliseconds since the Unix epoch,  UTC,  as Clojure's
+//! java.util.Date does,  and read and print it as an RFC3339 timestamp.  Reading accepts the
+//! same subset of RFC3339 Clojure does,  where everything after the year is optional, and an
+//! offset other than Z is folded into the instant itself:
+//!
+//!   #inst "2020"                       => #inst "2020-01-01T00:00:00.000-00:00"
+//!   #inst "2020-06-01T12:00:00+02:00"  => #inst "2020-06-01T10:00:00.000-00:00"
+use std::fmt;
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,PartialOrd,Ord)]
+pub struct Inst {
+    /// Milliseconds since 1970-01-01T00:00:00Z
+    pub millis: i64
+}
+impl Inst {
+    pub fn from_millis(millis: i64) -> Inst {
+	Inst { millis }
+    }
+    /// Parses an RFC3339 timestamp,  or None if timestamp isn't one,  or names a date or time
+    /// that doesn't exist (a 13th month, a 30th of February, a 24th hour ..)
+    ///
+    ///   YYYY(-MM(-DD(THH(:mm(:ss(.fraction)))))) followed by Z,  +HH:mm,  -HH:mm or nothing
+    pub fn parse(timestamp: &str) -> Option<Inst> {
+	let mut input = timestamp.as_bytes();
+	let year = digits(&mut input,4)?;
+	let (mut month,mut day) = (1,1);
+	let (mut hour,mut minute,mut second,mut millis) = (0,0,0,0);
+	// Each part is only there if the one before it is 
+	if let Some(given_month) = preceded_digits(&mut input,b'-',2) {
+	    month = given_month?;
+	    if let Some(given_day) = preceded_digits(&mut input,b'-',2) {
+		day = given_day?;
+		if let Some(given_hour) = preceded_digits(&mut input,b'T',2) {
+		    hour = given_hour?;
+		    if let Some(given_minute) = preceded_digits(&mut input,b':',2) {
+			minute = given_minute?;
+			if let Some(given_second) = preceded_digits(&mut input,b':',2) {
+			    second = given_second?;
+			    if input.first() == Some(&b'.') {
+				input = &input[1..];
+				let fraction_len = input.iter().take_while(|byte| byte.is_ascii_digit()).count();
+				if fraction_len == 0 {
+				    return None;
+				}
+				// We keep milliseconds;  the digits past them we let go
+				let fraction = format!("{:0<3}",std::str::from_utf8(&input[..fraction_len.min(3)]).ok()?);
+				millis = fraction.parse::<i64>().ok()?;
+				input = &input[fraction_len..];
+			    }
+			}
+		    }
+		}
+	    }
+	}
+	let offset_minutes = match input {
+	    [] | [b'Z'] => 0,
+	    [sign @ (b'+' | b'-'),..] => {
+		let sign = if *sign == b'-' { -1 } else { 1 };
+		input = &input[1..];
+		let offset_hour = digits(&mut input,2)?;
+		let offset_minute = preceded_digits(&mut input,b':',2)??;
+		if !input.is_empty() || offset_hour > 23 || offset_minute > 59 {
+		    return None;
+		}
+		sign * (offset_hour * 60 + offset_minute)
+	    },
+	    _ => return None
+	};
+	// A leap second can only come at the end of a minute
+	let max_second = if minute == 59 { 60 } else { 59 };
+	if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year,month)
+	    || hour > 23 || minute > 59 || second > max_second {
+	    return None;
+	}
+	let days = days_from_civil(year,month,day);
+	let millis_of_day = ((hour * 60 + minute - offset_minutes) * 60 + second) * 1000 + millis;
+	Some(Inst::from_millis(days * MILLIS_PER_DAY + millis_of_day))
+    }
+    /// Our timestamp in UTC;  2020-01-01T12:00:00.000-00:00,  as Clojure prints it
+    pub fn timestamp(&self) -> String {
+	let days = self.millis.div_euclid(MILLIS_PER_DAY);
+	let millis_of_day = self.millis.rem_euclid(MILLIS_PER_DAY);
+	let (year,month,day) = civil_from_days(days);
+	format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}-00:00",
+		year,month,day,
+		millis_of_day / 3_600_000,
+		millis_of_day / 60_000 % 60,
+		millis_of_day / 1000 % 60,
+		millis_of_day % 1000)
+    }
+}
+impl fmt::Display for Inst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	write!(f,"#inst \"{}\"",self.timestamp())
+    }
+}
+
+/// Takes exactly count digits off the front of input
+fn digits(input: &mut &[u8],count: usize) -> Option<i64> {
+    if input.len() < count || !input[..count].iter().all(|byte| byte.is_ascii_digit()) {
+	return None;
+    }
+    let number = std::str::from_utf8(&input[..count]).ok()?.parse().ok()?;
+    *input = &input[count..];
+    Some(number)
+}
+/// Takes separator followed by count digits off the front of input;  None if separator isn't
+/// there at all,  Some(None) if it is but the digits after it aren't
+fn preceded_digits(input: &mut &[u8],separator: u8,count: usize) -> Option<Option<i64>> {
+    // Right after our year or month,  -05 is the next part of our date,  never an offset 
+    if input.first() != Some(&separator) || !input.get(1).is_some_and(|byte| byte.is_ascii_digit()) {
+	return None;
+    }
+    let mut after_separator = &input[1..];
+    let number = digits(&mut after_separator,count);
+    if number.is_some() {
+	*input = after_separator;
+    }
+    Some(number)
+}
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+fn days_in_month(year: i64,month: i64) -> i64 {
+    match month {
+	2 if is_leap_year(year) => 29,
+	2 => 28,
+	4 | 6 | 9 | 11 => 30,
+	_ => 31
+    }
+}
+// These two are Howard Hinnant's algorithms for the proleptic Gregorian calendar;
+// http://howardhinnant.github.io/date_algorithms.html
+/// Days since 1970-01-01 of year-month-day
+fn days_from_civil(year: i64,month: i64,day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+/// The (year,month,day) days days after 1970-01-01
+fn civil_from_days(days: i64) -> (i64,i64,i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year,month,day)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::inst::Inst;
+
+    #[test]
+    fn test_parse_inst()
+    {
+	let timestamp = |input: &str| Inst::parse(input).map(|inst| inst.timestamp());
+	assert_eq!(Inst::parse("1970-01-01T00:00:00Z"),Some(Inst::from_millis(0)));
+	assert_eq!(timestamp("2020"),Some(String::from("2020-01-01T00:00:00.000-00:00")));
+	assert_eq!(timestamp("2020-02-29"),Some(String::from("2020-02-29T00:00:00.000-00:00")));
+	assert_eq!(timestamp("2020-06-01T12:30:15.5Z"),Some(String::from("2020-06-01T12:30:15.500-00:00")));
+	assert_eq!(timestamp("2020-06-01T12:00:00.123456-00:00"),Some(String::from("2020-06-01T12:00:00.123-00:00")));
+	assert_eq!(timestamp("2020-06-01T01:00:00+02:00"),Some(String::from("2020-05-31T23:00:00.000-00:00")));
+	assert_eq!(timestamp("1969-12-31T23:59:59.999Z"),Some(String::from("1969-12-31T23:59:59.999-00:00")));
+	assert_eq!(Inst::parse("1969-12-31T23:59:59.999Z"),Some(Inst::from_millis(-1)));
+	assert_eq!(Inst::parse("2019-02-29"),None);
+	assert_eq!(Inst::parse("2020-13-01"),None);
+	assert_eq!(Inst::parse("2020-01-01T24:00:00"),None);
+	assert_eq!(Inst::parse("2020-01-01T12:00:00+2:00"),None);
+	assert_eq!(Inst::parse("20"),None);
+	assert_eq!(Inst::parse("yesterday"),None);
+    }
+}
@@ -0,0 +1,116 @@
+//! Keywords;  :name,  :ns/name
+//!
+//! Unlike a symbol,  a keyword never means anything but itself,  which is what makes them
+//! good map keys.  They're interned -- there's only ever one :name -- so comparing two
+//! keywords is just comparing pointers
+use crate::value::Value;
+use crate::ifn::IFn;
+use crate::condition;
+
+use std::collections::HashSet;
+use std::cell::RefCell;
+use std::hash::{Hash,Hasher};
+use std::rc::Rc;
+use std::fmt;
+
+thread_local! {
+    static KEYWORDS: RefCell<HashSet<Rc<str>>> = RefCell::new(HashSet::new());
+}
+
+#[derive(Clone,Debug)]
+pub struct Keyword {
+    // Our full name,  without the colon;  name,  or ns/name
+    full_name: Rc<str>
+}
+impl Keyword {
+    /// The keyword for full_name ("name", or "ns/name"),  creating it if this is the first
+    /// time we've seen it
+    pub fn intern(full_name: &str) -> Keyword
+    {
+	KEYWORDS.with(|keywords| {
+	    let mut keywords = keywords.borrow_mut();
+	    if let Some(interned) = keywords.get(full_name) {
+		return Keyword { full_name: Rc::clone(interned) };
+	    }
+	    let interned : Rc<str> = Rc::from(full_name);
+	    keywords.insert(Rc::clone(&interned));
+	    Keyword { full_name: interned }
+	})
+    }
+    /// :ns/name => name
+    pub fn name(&self) -> &str {
+	match self.full_name.split_once('/') {
+	    Some((_,name)) if !name.is_empty() => name,
+	    _ => &self.full_name
+	}
+    }
+    /// :ns/name => ns,  :name => None
+    pub fn ns(&self) -> Option<&str> {
+	match self.full_name.split_once('/') {
+	    Some((ns,name)) if !ns.is_empty() && !name.is_empty() => Some(ns),
+	    _ => None
+	}
+    }
+    pub fn full_name(&self) -> &str {
+	&self.full_name
+    }
+}
+impl PartialEq for Keyword {
+    fn eq(&self, other: &Keyword) -> bool {
+	Rc::ptr_eq(&self.full_name,&other.full_name)
+    }
+}
+impl Eq for Keyword {}
+impl Hash for Keyword {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+	self.full_name.hash(state);
+    }
+}
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	write!(f,":{}",self.full_name)
+    }
+}
+/// (:name {:name "Blah"}) => "Blah",  (:age {:name "Blah"} 20) => 20
+///
+/// A keyword looks itself up in whatever it's given,  giving back the default (or nil)
+/// if it isn't there,  or if what it's given isn't a map (or set) at all
+impl IFn for Keyword {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.is_empty() || args.len() > 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1-2)",args.len()));
+	}
+	match args[0] {
+	    // (:name map default) is just (map :name default) 
+	    Value::PersistentListMap(plistmap) => {
+		let keyword = Value::Keyword(self.clone());
+		let mut lookup_args = vec![&keyword];
+		lookup_args.extend(args.get(1));
+		plistmap.invoke(lookup_args)
+	    },
+	    // (:a #{:a}) => :a 
+	    Value::PersistentHashSet(phashset) => match phashset.get(&Rc::new(Value::Keyword(self.clone()))) {
+		Some(member) => (*member).clone(),
+		None => args.get(1).map_or(Value::Nil,|default| (*default).clone())
+	    },
+	    _ => args.get(1).map_or(Value::Nil,|default| (*default).clone())
+	}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keyword::Keyword;
+
+    #[test]
+    fn test_intern()
+    {
+	let keyword = Keyword::intern("ns/name");
+	assert_eq!(keyword,Keyword::intern("ns/name"));
+	assert_ne!(keyword,Keyword::intern("name"));
+	assert_eq!(keyword.name(),"name");
+	assert_eq!(keyword.ns(),Some("ns"));
+	assert_eq!(Keyword::intern("name").ns(),None);
+	assert_eq!(keyword.to_string(),":ns/name");
+    }
+}
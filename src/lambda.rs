@@ -1,16 +1,30 @@
 use crate::environment::Environment;
 use crate::ifn::IFn;
-use crate::value::{Value,ToValue,Evaluable};
+use crate::ifn::{Invocation,RecursionPoint};
+use crate::value;
+use crate::value::{Value,ToValue};
+use crate::condition;
 use crate::persistent_list::ToPersistentList;
 use std::rc::Rc;
 use crate::symbol::Symbol;
 
 #[derive(Debug,Clone)]
 pub struct Fn {
-    pub body: Rc<Value>,
     // Closed over variables 
     pub enclosing_environment: Rc<Environment>,
-    pub arg_syms: Vec<Symbol>
+    pub arg_syms: Vec<Symbol>,
+    // Where a recur in our body jumps back to;  our args, minus any & 
+    recursion_point: Rc<RecursionPoint>
+}
+impl Fn {
+    pub fn new(body: Rc<Value>, enclosing_environment: Rc<Environment>, arg_syms: Vec<Symbol>) -> Fn {
+	let recursion_point = Rc::new(RecursionPoint {
+	    params: arg_syms.iter().filter(|sym| sym.name != "&").cloned().collect(),
+	    body,
+	    environment: Rc::clone(&enclosing_environment)
+	});
+	Fn { enclosing_environment, arg_syms, recursion_point }
+    }
 }
 impl ToValue for Fn {
     fn to_value(&self) -> Value {
@@ -19,12 +33,16 @@ impl ToValue for Fn {
 }
 impl IFn for Fn {
     fn invoke(&self,args: Vec<&Value>) -> Value {
+	value::eval_invocation(self.invoke_tail(args)).to_value()
+    }
+    /// Binds our arguments,  and leaves our body for the caller to evaluate 
+    fn invoke_tail(&self,args: Vec<&Value>) -> Invocation {
 	let local_environment = Rc::new(Environment::new_local_environment(Rc::clone(&self.enclosing_environment)));
 
 	let argc = self.arg_syms.len();
 	
 	let mut var_args = false;
-	if(argc >= 2) { 
+	if argc >= 2 { 
 	    if let Some(sym) = self.arg_syms.get(argc - 2)
 	    {
 		if sym.to_string() == "&" { 
@@ -36,15 +54,15 @@ impl IFn for Fn {
 	}
 	
 	if !var_args && args.len() != argc {
-	    return Value::Condition(format!("Wrong number of arguments given to function (Given: {}, Expected: {})",args.len(),argc));
+	    return Invocation::Done(Rc::new(condition::raise(format!("Wrong number of arguments given to function (Given: {}, Expected: {})",args.len(),argc))));
 	}
 
 	for (i,arg) in args.iter().enumerate() {
 	    let curr_sym = self.arg_syms.get(i).unwrap();
 	    // We can bind the rest of the arguments, then, to the next variable and blow this popsicle stand
 	    if curr_sym.to_string() == "&" {
-		if (!var_args) {
-		    return Value::Condition(String::from("Invalid function argument '&' in non-variable-argument function definition"));
+		if !var_args {
+		    return Invocation::Done(Rc::new(condition::raise(String::from("Invalid function argument '&' in non-variable-argument function definition"))));
 		}
 		let last_sym = self.arg_syms.get(i + 1).unwrap();
 		let rest_args = args.get(i..).unwrap().to_vec().into_list().to_rc_value();
@@ -53,6 +71,6 @@ impl IFn for Fn {
 	    }
 	    local_environment.insert(curr_sym.clone(),arg.to_rc_value());
 	}
-	self.body.eval(local_environment)
+	Invocation::Enter(Rc::clone(&self.recursion_point),local_environment)
     }
 }
@@ -0,0 +1,59 @@
+//! Metadata;  a map of data about a value,  rather than part of the value itself
+//!
+//!    ^{:doc "Adds one"} [x]   ^:private x   ^String s
+//!
+//! Symbols,  collections and functions can carry metadata.  It rides along with the value,
+//! but never changes what the value is;  two values differing only in their metadata are
+//! still equal (and hash the same)
+use crate::value::{Value,ToValue};
+use crate::ifn::{IFn,Invocation};
+use crate::persistent_list_map::{PersistentListMap,IPersistentListMap,ToPersistentListMapIter};
+
+use std::rc::Rc;
+
+// Based on clojure.lang.IMeta and clojure.lang.IObj
+/// Something that may carry metadata
+pub trait IMeta {
+    fn meta(&self) -> Option<Rc<PersistentListMap>>;
+}
+/// Something we can make a copy of with different metadata
+pub trait IObj : IMeta {
+    fn with_meta(&self,meta: Option<Rc<PersistentListMap>>) -> Self;
+}
+
+/// meta,  with the entries of more added to it (replacing any with the same key)
+pub fn merge_meta(meta: Option<Rc<PersistentListMap>>,more: &PersistentListMap) -> Rc<PersistentListMap> {
+    let mut merged = meta.unwrap_or_else(|| Rc::new(PersistentListMap::Empty(None)));
+    for entry in more.iter() {
+	merged = merged.assoc(entry.key,entry.val);
+    }
+    merged
+}
+
+/// A fn given metadata with with-meta;  otherwise,  just the fn it wraps
+#[derive(Debug,Clone)]
+pub struct FnWithMeta {
+    ifn: Rc<dyn IFn>,
+    meta: Option<Rc<PersistentListMap>>
+}
+impl FnWithMeta {
+    pub fn new(ifn: Rc<dyn IFn>,meta: Option<Rc<PersistentListMap>>) -> FnWithMeta {
+	FnWithMeta { ifn, meta }
+    }
+}
+impl ToValue for FnWithMeta {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for FnWithMeta {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	self.ifn.invoke(args)
+    }
+    fn invoke_tail(&self,args: Vec<&Value>) -> Invocation {
+	self.ifn.invoke_tail(args)
+    }
+    fn meta(&self) -> Option<Rc<PersistentListMap>> {
+	self.meta.clone()
+    }
+}
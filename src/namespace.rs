@@ -1,7 +1,5 @@
 use crate::value::{Value};
-use crate::value::ToValue;
 use crate::Symbol;
-use crate::rust_core::{AddFn,StrFn};
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -9,24 +7,52 @@ use std::cell::RefCell;
 #[derive(Debug,Clone)]
 pub struct Namespace {
     pub name: Symbol,
-    mappings: RefCell<HashMap<Symbol,Rc<Value>>>
+    mappings: RefCell<HashMap<Symbol,Rc<Value>>>,
+    // What we call other namespaces;  set for clojure.set,  after (alias 'set 'clojure.set) 
+    aliases: RefCell<HashMap<Symbol,Symbol>>
 }
 impl Namespace {
     pub fn new(name: Symbol, mappings: RefCell<HashMap<Symbol,Rc<Value>>>) -> Namespace {
-	Namespace { name,mappings } 
+	Namespace { name,mappings,aliases: RefCell::new(HashMap::new()) } 
     }
     pub fn insert(&self,sym: Symbol, val: Rc<Value>)
     {
 	self.mappings.borrow_mut().insert(sym,val);
     }
-    pub fn get(&self, sym: &Symbol) -> Rc<Value>
+    pub fn try_get(&self, sym: &Symbol) -> Option<Rc<Value>>
     {
-	match self.mappings.borrow_mut().get(sym) {
-	    Some(val) => Rc::clone(val),
-	    None => Rc::new(Value::Condition(format!("Undefined symbol {}",sym.name)))
-	}
+	self.mappings.borrow().get(sym).map(Rc::clone)
+    }
+    /// From here on, alias/name means ns/name 
+    pub fn add_alias(&self,alias: Symbol,ns: Symbol)
+    {
+	self.aliases.borrow_mut().insert(alias,ns);
+    }
+    /// The name of the namespace we call alias,  if we call any that
+    pub fn resolve_alias(&self,alias: &Symbol) -> Option<Symbol>
+    {
+	self.aliases.borrow().get(alias).cloned()
     }
 }
 #[derive(Debug,Clone)]
 pub struct Namespaces(pub RefCell<HashMap<Symbol,Namespace>>);
-
+impl Namespaces {
+    /// Maps sym to val in the namespace named ns,  creating it if we must 
+    pub fn insert_into(&self,ns: &Symbol,sym: Symbol,val: Rc<Value>)
+    {
+	self.0.borrow_mut()
+	    .entry(ns.clone())
+	    .or_insert_with(|| Namespace::new(ns.clone(),RefCell::new(HashMap::new())))
+	    .insert(sym,val);
+    }
+    /// What sym means in the namespace named ns;  None if there's no such namespace,  and
+    /// Some(None) if it just doesn't map sym 
+    pub fn try_get_from(&self,ns: &Symbol,sym: &Symbol) -> Option<Option<Rc<Value>>>
+    {
+	self.0.borrow().get(ns).map(|namespace| namespace.try_get(sym))
+    }
+    pub fn contains(&self,ns: &Symbol) -> bool
+    {
+	self.0.borrow().contains_key(ns)
+    }
+}
@@ -0,0 +1,283 @@
+//! Our numeric tower;  how our number Values combine and promote
+//!
+//!   i32 / i64  ->  BigInt  ->  Ratio  ->  f64
+//!
+//! When two numbers meet,  the one lower down the tower is promoted to the other's type.
+//! Integers of any size are worked with as an i64 until they no longer fit, at which point
+//! we either raise an arithmetic condition (+, *, ..) or promote to a BigInt (+', *', ..),
+//! and once a float is involved,  everything is an f64
+use crate::value::{Value,ToValue};
+use crate::condition;
+use crate::condition::Condition;
+use crate::keyword::Keyword;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Zero,ToPrimitive,FromPrimitive};
+
+use std::cmp::Ordering;
+
+#[derive(Debug,Clone)]
+pub enum Number {
+    Integer(i64),
+    BigInt(BigInt),
+    Ratio(BigRational),
+    Float(f64)
+}
+/// Whether an i64 operation that overflows raises an arithmetic condition,  or carries on as
+/// a BigInt
+#[derive(Debug,Clone,Copy,PartialEq)]
+enum Overflow {
+    Raise,
+    Promote
+}
+// Two numbers,  promoted to the same type
+enum Promoted {
+    Integers(i64,i64),
+    BigInts(BigInt,BigInt),
+    Ratios(BigRational,BigRational),
+    Floats(f64,f64)
+}
+impl Number {
+    pub fn from_value(value: &Value) -> Option<Number> {
+	match value {
+	    Value::I32(i) => Some(Number::Integer(i64::from(*i))),
+	    Value::I64(i) => Some(Number::Integer(*i)),
+	    Value::BigInt(i) => Some(Number::BigInt(i.clone())),
+	    Value::Ratio(r) => Some(Number::Ratio(r.clone())),
+	    Value::F64(f) => Some(Number::Float(*f)),
+	    _ => None
+	}
+    }
+    /// A ratio that's come out whole is a BigInt
+    pub fn from_ratio(ratio: BigRational) -> Number {
+	if ratio.is_integer() {
+	    Number::BigInt(ratio.to_integer())
+	}
+	else {
+	    Number::Ratio(ratio)
+	}
+    }
+    /// A BigInt small enough to be an i64 is an i64
+    pub fn demote(self) -> Number {
+	match self {
+	    Number::BigInt(i) => match i.to_i64() {
+		Some(i) => Number::Integer(i),
+		None => Number::BigInt(i)
+	    },
+	    number => number
+	}
+    }
+    pub fn to_f64(&self) -> f64 {
+	match self {
+	    Number::Integer(i) => *i as f64,
+	    Number::BigInt(i) => i.to_f64().unwrap_or(f64::NAN),
+	    Number::Ratio(r) => r.to_f64().unwrap_or(f64::NAN),
+	    Number::Float(f) => *f
+	}
+    }
+    fn to_bigint(&self) -> BigInt {
+	match self {
+	    Number::Integer(i) => BigInt::from(*i),
+	    Number::BigInt(i) => i.clone(),
+	    Number::Ratio(r) => r.to_integer(),
+	    Number::Float(f) => BigInt::from_f64(f.trunc()).unwrap_or_else(BigInt::zero)
+	}
+    }
+    fn to_ratio(&self) -> BigRational {
+	match self {
+	    Number::Ratio(r) => r.clone(),
+	    number => BigRational::from_integer(number.to_bigint())
+	}
+    }
+    fn promote(self,other: Number) -> Promoted {
+	match (self,other) {
+	    (Number::Integer(a),Number::Integer(b)) => Promoted::Integers(a,b),
+	    (a @ Number::Float(_),b) | (a,b @ Number::Float(_)) => Promoted::Floats(a.to_f64(),b.to_f64()),
+	    (a @ Number::Ratio(_),b) | (a,b @ Number::Ratio(_)) => Promoted::Ratios(a.to_ratio(),b.to_ratio()),
+	    (a,b) => Promoted::BigInts(a.to_bigint(),b.to_bigint())
+	}
+    }
+    pub fn is_zero(&self) -> bool {
+	match self {
+	    Number::Integer(i) => *i == 0,
+	    Number::BigInt(i) => i.is_zero(),
+	    Number::Ratio(r) => r.is_zero(),
+	    Number::Float(f) => *f == 0.0
+	}
+    }
+    fn is_negative(&self) -> bool {
+	self.compare(&Number::Integer(0)) == Some(Ordering::Less)
+    }
+    /// Applies the op for whatever type we've promoted to;  integer_op returning None means
+    /// we've overflowed an i64
+    fn combine(self,
+	       other: Number,
+	       overflow: Overflow,
+	       integer_op: fn(i64,i64) -> Option<i64>,
+	       bigint_op: fn(BigInt,BigInt) -> BigInt,
+	       ratio_op: fn(BigRational,BigRational) -> BigRational,
+	       float_op: fn(f64,f64) -> f64) -> Result<Number,Value> {
+	match self.promote(other) {
+	    Promoted::Integers(a,b) => match (integer_op(a,b),overflow) {
+		(Some(i),_) => Ok(Number::Integer(i)),
+		(None,Overflow::Promote) => Ok(Number::BigInt(bigint_op(BigInt::from(a),BigInt::from(b)))),
+		(None,Overflow::Raise) => Err(arithmetic_condition(format!("Arithmetic Error: integer overflow ({}, {})",a,b)))
+	    },
+	    Promoted::BigInts(a,b) => Ok(Number::BigInt(bigint_op(a,b))),
+	    Promoted::Ratios(a,b) => Ok(Number::from_ratio(ratio_op(a,b))),
+	    Promoted::Floats(a,b) => Ok(Number::Float(float_op(a,b)))
+	}
+    }
+    pub fn add(self,other: Number) -> Result<Number,Value> {
+	self.combine(other,Overflow::Raise,i64::checked_add,|a,b| a + b,|a,b| a + b,|a,b| a + b)
+    }
+    pub fn subtract(self,other: Number) -> Result<Number,Value> {
+	self.combine(other,Overflow::Raise,i64::checked_sub,|a,b| a - b,|a,b| a - b,|a,b| a - b)
+    }
+    pub fn multiply(self,other: Number) -> Result<Number,Value> {
+	self.combine(other,Overflow::Raise,i64::checked_mul,|a,b| a * b,|a,b| a * b,|a,b| a * b)
+    }
+    /// add,  but promoting to a BigInt rather than overflowing;  +' 
+    pub fn add_promoting(self,other: Number) -> Result<Number,Value> {
+	self.combine(other,Overflow::Promote,i64::checked_add,|a,b| a + b,|a,b| a + b,|a,b| a + b)
+    }
+    pub fn subtract_promoting(self,other: Number) -> Result<Number,Value> {
+	self.combine(other,Overflow::Promote,i64::checked_sub,|a,b| a - b,|a,b| a - b,|a,b| a - b)
+    }
+    pub fn multiply_promoting(self,other: Number) -> Result<Number,Value> {
+	self.combine(other,Overflow::Promote,i64::checked_mul,|a,b| a * b,|a,b| a * b,|a,b| a * b)
+    }
+    /// Exact division;  integers that don't divide evenly make a ratio
+    pub fn divide(self,other: Number) -> Result<Number,Value> {
+	match self.promote(other) {
+	    // Dividing a float by zero is simply infinite (or NaN)
+	    Promoted::Floats(a,b) => Ok(Number::Float(a / b)),
+	    Promoted::Integers(_,0) => Err(divide_by_zero()),
+	    // (/ 4 2) => 2,  but (/ 4N 2) => 2N
+	    Promoted::Integers(a,b) => {
+		Ok(Number::from_ratio(BigRational::new(BigInt::from(a),BigInt::from(b))).demote())
+	    },
+	    Promoted::BigInts(_,b) if b.is_zero() => Err(divide_by_zero()),
+	    Promoted::BigInts(a,b) => Ok(Number::from_ratio(BigRational::new(a,b))),
+	    Promoted::Ratios(_,b) if b.is_zero() => Err(divide_by_zero()),
+	    Promoted::Ratios(a,b) => Ok(Number::from_ratio(a / b))
+	}
+    }
+    /// Division,  truncating towards zero
+    pub fn quot(self,other: Number) -> Result<Number,Value> {
+	if other.is_zero() {
+	    return Err(divide_by_zero());
+	}
+	match self.promote(other) {
+	    Promoted::Ratios(a,b) => Ok(Number::BigInt((a / b).to_integer())),
+	    promoted => Promoted::apply(promoted,i64::checked_div,|a,b| a / b,|a,b| (a / b).trunc())
+	}
+    }
+    /// The remainder of quot;  takes the sign of self
+    pub fn rem(self,other: Number) -> Result<Number,Value> {
+	if other.is_zero() {
+	    return Err(divide_by_zero());
+	}
+	match self.promote(other) {
+	    Promoted::Ratios(a,b) => {
+		let quot = BigRational::from_integer((&a / &b).to_integer());
+		Ok(Number::from_ratio(a - quot * b))
+	    },
+	    // Rust's % is already a remainder rather than a modulus
+	    promoted => Promoted::apply(promoted,i64::checked_rem,|a,b| a % b,|a,b| a % b)
+	}
+    }
+    /// Modulus;  takes the sign of other
+    pub fn modulo(self,other: Number) -> Result<Number,Value> {
+	let rem = self.rem(other.clone())?;
+	if !rem.is_zero() && rem.is_negative() != other.is_negative() {
+	    rem.add(other)
+	}
+	else {
+	    Ok(rem)
+	}
+    }
+    pub fn compare(&self,other: &Number) -> Option<Ordering> {
+	match self.clone().promote(other.clone()) {
+	    Promoted::Integers(a,b) => Some(a.cmp(&b)),
+	    Promoted::BigInts(a,b) => Some(a.cmp(&b)),
+	    Promoted::Ratios(a,b) => Some(a.cmp(&b)),
+	    Promoted::Floats(a,b) => a.partial_cmp(&b)
+	}
+    }
+    /// The exact ratio a float is written as;  (rationalize 0.1) => 1/10
+    pub fn rationalize(self) -> Result<Number,Value> {
+	match self {
+	    Number::Float(f) if !f.is_finite() => {
+		Err(arithmetic_condition(format!("Arithmetic Error: cannot rationalize {}",Value::F64(f))))
+	    },
+	    Number::Float(f) => {
+		// Display gives us the shortest decimal that reads back as f,  and never an exponent
+		let decimal = f.to_string();
+		let (whole,fraction) = match decimal.split_once('.') {
+		    Some((whole,fraction)) => (whole,fraction),
+		    None => (decimal.as_str(),"")
+		};
+		let numerator = format!("{}{}",whole,fraction).parse::<BigInt>().unwrap();
+		let denominator = num_traits::pow(BigInt::from(10),fraction.len());
+		Ok(Number::from_ratio(BigRational::new(numerator,denominator)))
+	    },
+	    number => Ok(number)
+	}
+    }
+    /// Truncates to a BigInt
+    pub fn truncate_to_bigint(self) -> Result<Number,Value> {
+	match self {
+	    Number::Float(f) if !f.is_finite() => {
+		Err(arithmetic_condition(format!("Arithmetic Error: cannot convert {} to a BigInt",Value::F64(f))))
+	    },
+	    number => Ok(Number::BigInt(number.to_bigint()))
+	}
+    }
+    pub fn into_value(self) -> Value {
+	match self {
+	    Number::Integer(i) => Value::I64(i),
+	    Number::BigInt(i) => Value::BigInt(i),
+	    Number::Ratio(r) => Value::Ratio(r),
+	    Number::Float(f) => Value::F64(f)
+	}
+    }
+}
+impl Promoted {
+    /// Applies the op for our type to what's left (anything but ratios)
+    fn apply(self,
+	     integer_op: fn(i64,i64) -> Option<i64>,
+	     bigint_op: fn(BigInt,BigInt) -> BigInt,
+	     float_op: fn(f64,f64) -> f64) -> Result<Number,Value> {
+	match self {
+	    Promoted::Integers(a,b) => integer_op(a,b).map(Number::Integer).ok_or_else(|| {
+		arithmetic_condition(format!("Arithmetic Error: integer overflow ({}, {})",a,b))
+	    }),
+	    Promoted::BigInts(a,b) => Ok(Number::BigInt(bigint_op(a,b))),
+	    Promoted::Floats(a,b) => Ok(Number::Float(float_op(a,b))),
+	    Promoted::Ratios(..) => unreachable!("ratios are handled by the caller")
+	}
+    }
+}
+
+/// Clojure's =;  like our Value equality,  except floats are compared as floats,  so ##NaN
+/// isn't = to itself.  Numbers of different categories (integer,  ratio,  float) are never
+/// equal;  (= 1 1.0) => false
+pub fn equiv(a: &Value,b: &Value) -> bool {
+    match (Number::from_value(a),Number::from_value(b)) {
+	(Some(Number::Float(a)),Some(Number::Float(b))) => a == b,
+	_ => a == b
+    }
+}
+
+/// Signals a condition of type :arithmetic-error
+pub fn arithmetic_condition(message: String) -> Value {
+    condition::signal(Condition {
+	condition_type: Keyword::intern("arithmetic-error").to_rc_value(),
+	..Condition::new(message)
+    })
+}
+pub fn divide_by_zero() -> Value {
+    arithmetic_condition(String::from("Arithmetic Error: Divide by zero"))
+}
@@ -0,0 +1,150 @@
+//! Persistent sets;  #{1 2 3}
+//!
+//! For now,  like our PersistentVector,  'persistent' just means we copy on conj and disj
+//! rather than share structure.  We remember the order our members came in,  so a set
+//! prints (and iterates) the same way every time,  and keep a HashSet alongside to look
+//! them up
+use crate::value::{Value,ToValue};
+use crate::ifn::IFn;
+use crate::condition;
+use crate::meta::{IMeta,IObj};
+use crate::persistent_list_map::PersistentListMap;
+
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash,Hasher};
+use std::iter::FromIterator;
+use std::rc::Rc;
+use std::fmt;
+
+#[derive(Debug,Clone,Default)]
+pub struct PersistentHashSet {
+    // Our members,  in the order they were added
+    vals: Vec<Rc<Value>>,
+    members: HashSet<Rc<Value>>,
+    meta: Option<Rc<PersistentListMap>>
+}
+impl PersistentHashSet {
+    pub fn empty() -> PersistentHashSet {
+	PersistentHashSet::default()
+    }
+    pub fn contains(&self,val: &Rc<Value>) -> bool {
+	self.members.contains(val)
+    }
+    /// The member equal to val,  if we have one;  (#{:a} :a) => :a
+    pub fn get(&self,val: &Rc<Value>) -> Option<Rc<Value>> {
+	self.members.get(val).map(Rc::clone)
+    }
+    /// This set with val added,  which is just this set if we already have it
+    pub fn conj(&self,val: Rc<Value>) -> PersistentHashSet {
+	let mut conjed = self.clone();
+	if conjed.members.insert(Rc::clone(&val)) {
+	    conjed.vals.push(val);
+	}
+	conjed
+    }
+    /// This set without val
+    pub fn disj(&self,val: &Rc<Value>) -> PersistentHashSet {
+	if !self.contains(val) {
+	    return self.clone();
+	}
+	let mut disjed = self.iter().filter(|member| member != val).collect::<PersistentHashSet>();
+	disjed.meta = self.meta.clone();
+	disjed
+    }
+    pub fn len(&self) -> usize {
+	self.vals.len()
+    }
+    pub fn is_empty(&self) -> bool {
+	self.vals.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = Rc<Value>> + '_ {
+	self.vals.iter().cloned()
+    }
+}
+// Two sets are equal when they have the same members,  in whatever order they came in (and
+// whatever their metadata) 
+impl PartialEq for PersistentHashSet {
+    fn eq(&self, other: &PersistentHashSet) -> bool {
+	self.members == other.members
+    }
+}
+impl Hash for PersistentHashSet {
+    // Likewise our hash can't depend on our order,  so we combine our members' hashes
+    // with something that doesn't care what order it sees them in
+    fn hash<H: Hasher>(&self, state: &mut H) {
+	let members_hash = self.vals.iter().fold(0_u64,|members_hash,val| {
+	    let mut hasher = DefaultHasher::new();
+	    val.hash(&mut hasher);
+	    members_hash.wrapping_add(hasher.finish())
+	});
+	members_hash.hash(state);
+    }
+}
+impl IMeta for PersistentHashSet {
+    fn meta(&self) -> Option<Rc<PersistentListMap>> {
+	self.meta.clone()
+    }
+}
+impl IObj for PersistentHashSet {
+    fn with_meta(&self,meta: Option<Rc<PersistentListMap>>) -> PersistentHashSet {
+	PersistentHashSet { meta, ..self.clone() }
+    }
+}
+impl fmt::Display for PersistentHashSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	let str = self.vals.iter().map(|rc_arg| {
+	    rc_arg.to_string_explicit()
+	}).collect::<Vec<std::string::String>>().join(" ");
+	write!(f, "#{{{}}}",str)
+    }
+}
+/// (#{:a :b} :a) => :a,  (#{:a :b} :c) => nil
+impl IFn for PersistentHashSet {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	self.get(&args[0].to_rc_value()).map_or(Value::Nil,|member| member.to_value())
+    }
+}
+/// Duplicates are dropped,  keeping the first one we see
+impl FromIterator<Rc<Value>> for PersistentHashSet {
+    fn from_iter<I: IntoIterator<Item=Rc<Value>>>(iter: I) -> Self {
+	let mut set = PersistentHashSet::empty();
+	for val in iter {
+	    if set.members.insert(Rc::clone(&val)) {
+		set.vals.push(val);
+	    }
+	}
+	set
+    }
+}
+impl From<Vec<Rc<Value>>> for PersistentHashSet {
+    fn from(item: Vec<Rc<Value>>) -> Self {
+	item.into_iter().collect::<PersistentHashSet>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::persistent_hash_set::PersistentHashSet;
+    use crate::value::ToValue;
+    use crate::keyword::Keyword;
+
+    #[test]
+    fn test_persistent_hash_set()
+    {
+	let a = Keyword::intern("a").to_rc_value();
+	let b = Keyword::intern("b").to_rc_value();
+	let set = vec![a.clone(),b.clone(),a.clone()].into_iter().collect::<PersistentHashSet>();
+	assert_eq!(set.len(),2);
+	assert_eq!(set.to_string(),"#{:a :b}");
+	let set2 = set.disj(&a);
+	assert!(set.contains(&a));
+	assert!(!set2.contains(&a));
+	assert_eq!(set2.conj(a.clone()),set);
+	assert_eq!(set2.conj(a.clone()).to_string(),"#{:b :a}");
+	assert_eq!(set.conj(b),set);
+    }
+}
@@ -4,40 +4,82 @@ use std::fmt::Debug;
 use std::iter::FromIterator;
 
 use crate::value::{Value,ToValue};
+use crate::meta::{IMeta,IObj};
+use crate::persistent_list_map::PersistentListMap;
 use std::hash::{Hash,Hasher};
 
-#[derive(Debug,Clone,PartialEq,Hash)]
+#[derive(Debug,Clone)]
 pub enum PersistentList {
     // @TODO refactor i32 (our len) into a usize
-    Cons(Rc<Value>,Rc<PersistentList>,i32),
-    Empty
+    // Our head,  our tail,  our len,  and our metadata 
+    Cons(Rc<Value>,Rc<PersistentList>,i32,Option<Rc<PersistentListMap>>),
+    // Our metadata 
+    Empty(Option<Rc<PersistentListMap>>)
 }
-// @TODO definitely don't do this
-#[derive(Debug,Clone,Hash)]
-struct EmptyHash {}
 
 use crate::persistent_list::PersistentList::{Empty,Cons};
 pub fn cons_rc(head: Rc<Value>, tail: Rc<PersistentList>) -> PersistentList
 {
-    if let Cons(_,_,old_count) = &*tail {
-        Cons(Rc::clone(&head),Rc::clone(&tail),old_count + 1)
+    if let Cons(_,_,old_count,_) = &*tail {
+        Cons(Rc::clone(&head),Rc::clone(&tail),old_count + 1,None)
     }
     else {
-        Cons(Rc::clone(&head),Rc::clone(&tail),1)
+        Cons(Rc::clone(&head),Rc::clone(&tail),1,None)
     }
     
 }
 
 /// For building a 'top level' list, that is the first to reference (and own) all of its values
+// Only our tests build lists this way,  for now 
+#[allow(dead_code)]
 pub fn cons(head: Value, tail: PersistentList) -> PersistentList
 {
     cons_rc(Rc::new(head),Rc::new(tail))
 }
+// Two lists with the same elements are equal,  whatever their metadata 
+impl PartialEq for PersistentList {
+    fn eq(&self, other: &PersistentList) -> bool {
+	self.len() == other.len() && self.elements().eq(other.elements())
+    }
+}
+impl Hash for PersistentList {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+	for element in self.elements() {
+	    element.hash(state);
+	}
+    }
+}
+impl IMeta for PersistentList {
+    fn meta(&self) -> Option<Rc<PersistentListMap>> {
+	match self {
+	    Cons(_,_,_,meta) | Empty(meta) => meta.clone()
+	}
+    }
+}
+impl IObj for PersistentList {
+    fn with_meta(&self,meta: Option<Rc<PersistentListMap>>) -> PersistentList {
+	match self {
+	    Cons(head,tail,count,_) => Cons(Rc::clone(head),Rc::clone(tail),*count,meta),
+	    Empty(_) => Empty(meta)
+	}
+    }
+}
 impl PersistentList {
+    /// Our elements,  without needing to put ourselves in an Rc first like iter does 
+    fn elements(&self) -> impl Iterator<Item = &Rc<Value>> {
+	let mut node = self;
+	std::iter::from_fn(move || match node {
+	    Cons(head,tail,..) => {
+		node = tail;
+		Some(head)
+	    },
+	    Empty(_) => None
+	})
+    }
     pub fn len(&self) -> i32{
 	
         match self {
-            Cons(_,_,count) => *count,
+            Cons(_,_,count,_) => *count,
             _ => 0
         }
     }
@@ -52,11 +94,6 @@ impl PersistentList {
 /// Convert to a PersistentList 
 pub trait ToPersistentList {
     fn into_list(self) -> PersistentList;
-    fn into_list_value(self : Self) -> Value where
-	Self: Sized
-    {
-	self.into_list().to_value()
-    }
 }
 impl ToPersistentList for Vec<&Value> {
     fn into_list(self) -> PersistentList {
@@ -71,7 +108,7 @@ impl ToPersistentList for Vec<Rc<Value>> {
 impl fmt::Display for PersistentList {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 	let str = match self {
-	    Cons(head,tail,count) => {
+	    Cons(head,tail,count,_) => {
 		let tail_str = tail.iter().map(|rc_arg| {
 		    rc_arg.to_string_explicit()
 		}).collect::<Vec<std::string::String>>().join(" ");
@@ -82,7 +119,7 @@ impl fmt::Display for PersistentList {
 		    format!("({} {})",head.to_string_explicit(),tail_str)
 		}
 	    },
-	    Empty => std::string::String::from("()")
+	    Empty(_) => std::string::String::from("()")
 	};
 	write!(f, "{}",str)
     }
@@ -124,9 +161,9 @@ impl Iterator for PersistentListIter {
     type Item = Rc<Value>;
     fn next(&mut self) -> Option<Self::Item> {
         match &*(self.list.clone()) {
-            Cons(first,rest,_) => {
-                self.list = Rc::clone(&rest);
-                Some(Rc::clone(&first))
+            Cons(first,rest,..) => {
+                self.list = Rc::clone(rest);
+                Some(Rc::clone(first))
             },
             _ => None
         }
@@ -134,7 +171,7 @@ impl Iterator for PersistentListIter {
 }
 impl FromIterator<Rc<Value>> for PersistentList {
     fn from_iter<I: IntoIterator<Item=Rc<Value>>>(iter: I) -> Self {
-        let mut retval = PersistentList::Empty;
+        let mut retval = PersistentList::Empty(None);
         // @TODO see if we can directly loop through our original iter backwards, and avoid 
         // dumping into this vector just to loop through again backwards 
         let mut coll_as_vec = vec![];
@@ -145,7 +182,7 @@ impl FromIterator<Rc<Value>> for PersistentList {
         }
         for i in coll_as_vec.iter().rev() {
             count += 1;
-            retval = Cons(Rc::clone(i),Rc::new(retval),count);
+            retval = Cons(Rc::clone(i),Rc::new(retval),count,None);
         }
         
         retval
@@ -162,9 +199,9 @@ mod tests {
     #[test]
     fn test_persistent_list_count()
     {
-	let plist = cons(1_i32.to_value(),cons(2_i32.to_value(),Empty));
-	let plist2 = cons(1_i32.to_value(),cons(2_i32.to_value(),cons(3_i32.to_value(),Empty)));
-	let plist3 = Empty;
+	let plist = cons(1_i32.to_value(),cons(2_i32.to_value(),Empty(None)));
+	let plist2 = cons(1_i32.to_value(),cons(2_i32.to_value(),cons(3_i32.to_value(),Empty(None))));
+	let plist3 = Empty(None);
 	let plist4 = cons_rc(4_i32.to_rc_value(),Rc::new(plist2.clone()));
 	let rc_plist4 = Rc::new(plist4.clone());
 	let plist5 = cons_rc(5_i32.to_rc_value(),Rc::clone(&rc_plist4));
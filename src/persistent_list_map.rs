@@ -15,42 +15,91 @@
 
 use crate::maps::MapEntry;
 use crate::value::Value;
-use crate::symbol::Symbol;
+use crate::ifn::IFn;
+use crate::condition;
+use crate::meta::{IMeta,IObj};
 
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
 use std::rc::Rc;
 use std::fmt;
+use std::hash::{Hash,Hasher};
 use std::iter::FromIterator;
 use std::convert::From;
 
 
-#[derive(Debug,Clone,PartialEq,Hash)]
+#[derive(Debug,Clone)]
 pub enum PersistentListMap {
-    Map(Rc<PersistentListMap>,MapEntry),
-    Empty
+    // Our parent,  our newest entry,  and our metadata 
+    Map(Rc<PersistentListMap>,MapEntry,Option<Rc<PersistentListMap>>),
+    // Our metadata 
+    Empty(Option<Rc<PersistentListMap>>)
+}
+// Two maps are equal when they map the same keys to the same values,  no matter what order
+// they were assoc'd in (or what keys they've since shadowed,  or what their metadata is)
+impl PartialEq for PersistentListMap {
+    fn eq(&self, other: &PersistentListMap) -> bool {
+	let entries = self.iter().collect::<Vec<MapEntry>>();
+	entries.len() == other.iter().count()
+	    && entries.iter().all(|entry| other.contains_key(&entry.key) && other.get(&entry.key) == entry.val)
+    }
+}
+impl Hash for PersistentListMap {
+    // Likewise,  we combine our entries' hashes in a way that doesn't care what order they're in 
+    fn hash<H: Hasher>(&self, state: &mut H) {
+	let entries_hash = self.iter().fold(0_u64,|entries_hash,entry| {
+	    let mut hasher = DefaultHasher::new();
+	    entry.hash(&mut hasher);
+	    entries_hash.wrapping_add(hasher.finish())
+	});
+	entries_hash.hash(state);
+    }
+}
+impl IMeta for PersistentListMap {
+    fn meta(&self) -> Option<Rc<PersistentListMap>> {
+	match self {
+	    PersistentListMap::Map(_,_,meta) | PersistentListMap::Empty(meta) => meta.clone()
+	}
+    }
+}
+impl IObj for PersistentListMap {
+    fn with_meta(&self,meta: Option<Rc<PersistentListMap>>) -> PersistentListMap {
+	match self {
+	    PersistentListMap::Map(parent,entry,_) => PersistentListMap::Map(Rc::clone(parent),entry.clone(),meta),
+	    PersistentListMap::Empty(_) => PersistentListMap::Empty(meta)
+	}
+    }
 }
 // Again, only using strange IBlah convention to reflect the Clojure base
 // @TODO really though .. just rethink this 
 /// A PersistentListMap.
 pub trait IPersistentListMap {
     fn get(&self,key: &Rc<Value>) -> Rc<Value>;
+    /// Whether key is in our map at all;  get alone can't tell a missing key from one mapped to nil 
+    fn contains_key(&self,key: &Rc<Value>) -> bool;
     fn assoc(&self,key: Rc<Value>, value: Rc<Value>) -> Self;
 }
 impl IPersistentListMap for PersistentListMap {
     // @TODO make fn of ILookup 
     fn get(&self,key: &Rc<Value>) -> Rc<Value> {
 	match self {
-	    PersistentListMap::Map(parent,entry) =>  { 
+	    PersistentListMap::Map(parent,entry,_) =>  { 
 		if entry.key == *key {
 		    return Rc::clone(&entry.val);
 		}
 		parent.get(key)
 	    },
-	    PersistentListMap::Empty => Rc::new(Value::Nil)
+	    PersistentListMap::Empty(_) => Rc::new(Value::Nil)
+	}
+    }
+    fn contains_key(&self,key: &Rc<Value>) -> bool {
+	match self {
+	    PersistentListMap::Map(parent,entry,_) => entry.key == *key || parent.contains_key(key),
+	    PersistentListMap::Empty(_) => false
 	}
     }
     fn assoc(&self,key: Rc<Value>, val: Rc<Value>) -> PersistentListMap {
-	PersistentListMap::Map(Rc::new(self.clone()),MapEntry{key,val})
+	PersistentListMap::Map(Rc::new(self.clone()),MapEntry{key,val},IMeta::meta(self))
     }
 } 
 
@@ -58,17 +107,36 @@ impl IPersistentListMap for Rc<PersistentListMap> {
     // @TODO make fn of ILookup 
     fn get(&self,key: &Rc<Value>) -> Rc<Value> {
 	match &**self {
-	    PersistentListMap::Map(parent,entry) =>  { 
+	    PersistentListMap::Map(parent,entry,_) =>  { 
 		if entry.key == *key {
 		    return Rc::clone(&entry.val);
 		}
 		parent.get(key)
 	    },
-	    PersistentListMap::Empty => Rc::new(Value::Nil)
+	    PersistentListMap::Empty(_) => Rc::new(Value::Nil)
 	}
     }
+    fn contains_key(&self,key: &Rc<Value>) -> bool {
+	(**self).contains_key(key)
+    }
     fn assoc(&self,key: Rc<Value>, val: Rc<Value>) -> Rc<PersistentListMap> {
-	Rc::new(PersistentListMap::Map(Rc::clone(self),MapEntry{key,val}))
+	Rc::new(PersistentListMap::Map(Rc::clone(self),MapEntry{key,val},IMeta::meta(&**self)))
+    }
+}
+
+/// ({:name "Blah"} :name) => "Blah",  ({:name "Blah"} :age 20) => 20 
+impl IFn for PersistentListMap {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.is_empty() || args.len() > 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1-2)",args.len()));
+	}
+	let key = Rc::new(args[0].clone());
+	if self.contains_key(&key) {
+	    self.get(&key).as_ref().clone()
+	}
+	else {
+	    args.get(1).map_or(Value::Nil,|default| (*default).clone())
+	}
     }
 }
 
@@ -104,7 +172,7 @@ impl Iterator for PersistentListMapIter {
     type Item = MapEntry;
     fn next(&mut self) -> Option<Self::Item> {
         match &*(Rc::clone(&self.node)) {
-            PersistentListMap::Map(parent,mapentry) => {
+            PersistentListMap::Map(parent,mapentry,_) => {
 		self.node = Rc::clone(parent);
 		if self.seen.contains_key(&mapentry.key) {
 		    return self.next();
@@ -112,7 +180,7 @@ impl Iterator for PersistentListMapIter {
 		self.seen.insert(mapentry.key.clone(),true);
                 Some(mapentry.clone())
             },
-            PersistentListMap::Empty => None
+            PersistentListMap::Empty(_) => None
         }
     }
 }
@@ -133,10 +201,10 @@ impl ToPersistentListMapIter for PersistentListMap {
 
 impl FromIterator<MapEntry> for PersistentListMap {
     fn from_iter<I: IntoIterator<Item=MapEntry>>(iter: I) -> Self {
-	let mut map_so_far = PersistentListMap::Empty;
+	let mut map_so_far = PersistentListMap::Empty(None);
 	
         for i in iter {
-	    map_so_far = PersistentListMap::Map(Rc::new(map_so_far),i.clone());
+	    map_so_far = PersistentListMap::Map(Rc::new(map_so_far),i.clone(),None);
         }
 	map_so_far
     }
@@ -157,7 +225,7 @@ impl fmt::Display for PersistentListMap {
 	    first_loop = false;
 	    as_str.push_str(&format!("{} {}",mapentry.key.to_string_explicit(),mapentry.val.to_string_explicit()));
 	}
-	as_str.push_str("}");
+	as_str.push('}');
 
 	write!(f, "{}",as_str)
     }
@@ -165,22 +233,23 @@ impl fmt::Display for PersistentListMap {
 #[cfg(test)]
 mod tests { 
     use crate::persistent_list_map::*;
+    use crate::symbol::Symbol;
     use crate::value::ToValue;
     #[test]
     fn test_persistent_list_map()
     {
-	let empty = PersistentListMap::Empty;
-	let map1 = vec![MapEntry { key: Symbol::intern("a").to_value(), val: 15_i32.to_rc_value()},
-			MapEntry { key: Symbol::intern("b").to_value(), val: "stuff".to_rc_value()}].into_iter().collect::<PersistentListMap>();
+	let _empty = PersistentListMap::Empty(None);
+	let map1 = vec![MapEntry { key: Symbol::intern("a").to_rc_value(), val: 15_i32.to_rc_value()},
+			MapEntry { key: Symbol::intern("b").to_rc_value(), val: "stuff".to_rc_value()}].into_iter().collect::<PersistentListMap>();
 	println!("{}",map1);
-	let map2 = map1.assoc(Symbol::intern("c").to_value(),100_i32.to_rc_value());
+	let map2 = map1.assoc(Symbol::intern("c").to_rc_value(),100_i32.to_rc_value());
 	println!("{}",map1);
 	println!("{}",map2);
-	let map3 = map1.assoc(Symbol::intern("a").to_value(),100_i32.to_rc_value());
+	let map3 = map1.assoc(Symbol::intern("a").to_rc_value(),100_i32.to_rc_value());
 	println!("{}",map1);
 	println!("{}",map2);
 	println!("{}",map3);
-	let map4 = map2.assoc(Symbol::intern("a").to_value(),100_i32.to_rc_value());
+	let map4 = map2.assoc(Symbol::intern("a").to_rc_value(),100_i32.to_rc_value());
 	println!("{}",map1);
 	println!("{}",map2);
 	println!("{}",map3);
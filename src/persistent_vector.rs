@@ -6,10 +6,37 @@ use std::hash::{Hash,Hasher};
 use std::convert::From;
 
 use crate::value::{Value,ToValue};
+use crate::ifn::IFn;
+use crate::condition;
+use crate::numbers::Number;
+use crate::meta::{IMeta,IObj};
+use crate::persistent_list_map::PersistentListMap;
 
-#[derive(Debug,Clone,PartialEq,Hash)]
+#[derive(Debug,Clone)]
 pub struct PersistentVector {
-    pub vals: Vec<Rc<Value>>
+    pub vals: Vec<Rc<Value>>,
+    pub meta: Option<Rc<PersistentListMap>>
+}
+// Our metadata doesn't make us a different vector 
+impl PartialEq for PersistentVector {
+    fn eq(&self, other: &PersistentVector) -> bool {
+	self.vals == other.vals
+    }
+}
+impl Hash for PersistentVector {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+	self.vals.hash(state);
+    }
+}
+impl IMeta for PersistentVector {
+    fn meta(&self) -> Option<Rc<PersistentListMap>> {
+	self.meta.clone()
+    }
+}
+impl IObj for PersistentVector {
+    fn with_meta(&self,meta: Option<Rc<PersistentListMap>>) -> PersistentVector {
+	PersistentVector { vals: self.vals.clone(), meta }
+    }
 }
 impl fmt::Display for PersistentVector {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -20,18 +47,30 @@ impl fmt::Display for PersistentVector {
     }
 }
 
+/// ([:a :b :c] 1) => :b 
+impl IFn for PersistentVector {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match Number::from_value(args[0]) {
+	    Some(Number::Integer(ind)) if ind < 0 => {
+		condition::raise(format!("Index cannot be negative; Index ({})",ind))
+	    },
+	    Some(Number::Integer(ind)) => match self.vals.get(ind as usize) {
+		Some(val) => val.to_value(),
+		None => condition::raise(format!("Index out of bounds: Index ({}), Length: ({})",ind,self.vals.len()))
+	    },
+	    _ => condition::raise(format!("Type mismatch; Expected instance of clojure.lang.Integer,  Recieved type {}",args[0].type_tag()))
+	}
+    }
+}
+
 impl From<Vec<Rc<Value>>> for  PersistentVector {
     fn from(item: Vec<Rc<Value>>) -> Self {
 	item.into_iter().collect::<PersistentVector>()
     }
 }
-// impl Hash for PersistentVector { 
-//     fn hash<H: Hasher>(&self, state: &mut H) {
-// 	let as_vec = Rc::new(self.clone()).iter().collect::<Vec<Rc<Value>>>();
-// 	as_vec.hash(state)
-//     }
-// }
-//
 // Mostly to just make some code more concise
 // @TODO ~lookup proper rust conversion traits~
 // @TODO ok, proper conversions found, start removing these
@@ -39,11 +78,6 @@ pub trait ToPersistentVector {
     // Uses 'into' instead of typical 'to_..' because this is actually meant to be
     // (into [] self), a sort of building block of our eventual `into` function 
     fn into_vector(self) -> PersistentVector;
-    fn into_vector_value(self : Self) -> Value where
-	Self: Sized
-    {
-	self.into_vector().to_value()
-    }
 }
 impl ToPersistentVector for Vec<Rc<Value>> {
     fn into_vector(self) -> PersistentVector {
@@ -67,7 +101,7 @@ pub struct PersistentVectorIter {
 impl Iterator for PersistentVectorIter {
     type Item = Rc<Value>;
     fn next(&mut self) -> Option<Self::Item> {
-        let retval = (&*self.vector.vals).get(self.ind).map(|rc_val|{
+        let retval = self.vector.vals.get(self.ind).map(|rc_val|{
             Rc::clone(rc_val)
         });
         self.ind += 1;
@@ -86,6 +120,6 @@ impl FromIterator<Rc<Value>> for PersistentVector {
         for i in iter {
             coll_as_vec.push(i);
         }
-        PersistentVector {vals: coll_as_vec}
+        PersistentVector {vals: coll_as_vec, meta: None}
     }
 }
@@ -1,8 +1,8 @@
 //! The reader.  The part that reads plain text and parses it into Clojure structures, which are
 //! themselves code. 
 //!
-//! Right now there's no sort of data kept track by the reader at any point, so there's no real
-//! reader data structure here -- this is just a plain module, a bag of functions.  However,
+//! Mostly, this is just a plain module, a bag of functions;  the one reader data structure is
+//! LispReader,  which reads forms one at a time out of a stream (a file, or stdin).  However,
 //! I believe this will change -- especially as, for instance, we define the idea of reader conditionals,
 //! or even reader macros,  although the latter will likely be reserved for our interpreter here (but perhaps
 //! not;  since this is about being a 'free-er' Clojure, especially since it can't compete with it in raw
@@ -12,34 +12,146 @@ extern crate nom;
 use nom::{
     IResult,
     branch::alt,
-    error::convert_error,
+    combinator::cut,
+    error::ErrorKind,
     character::{is_alphabetic,is_alphanumeric},
-    character::complete::multispace0,
     character::is_digit,
-    bytes::complete::{take_while1,take_until,tag},
-    combinator::map_res,
-    sequence::{preceded,terminated}};
+    bytes::complete::{take_while1,tag},
+    sequence::preceded};
 
 use crate::value::{Value,ToValue};
-use crate::persistent_list::{ToPersistentList};
+use crate::persistent_list::{ToPersistentList,ToPersistentListIter,PersistentList};
 use crate::persistent_vector::{ToPersistentVector};
-use crate::persistent_list_map::{PersistentListMap,ToPersistentListMap};
+use crate::persistent_list_map::{ToPersistentListMap,ToPersistentListMapIter};
+use crate::persistent_hash_set::PersistentHashSet;
+use crate::meta;
 use crate::maps::MapEntry;
 use crate::symbol::Symbol;
-use std::rc::Rc;
+use crate::keyword::Keyword;
+use crate::numbers::Number;
+use crate::source::Source;
+use crate::reader_error::{ReaderError,ParseError,ParseErrorKind};
+use crate::data_readers;
+use crate::environment::Environment;
+use std::rc::{Rc,Weak};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::thread::LocalKey;
 
-use std::fs::File;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::Zero;
+
+/// What our parsers give back;  what they read and the input left after it,  or else why
+/// (and where in it) they gave up 
+pub type ReadResult<'a,O> = IResult<&'a [u8],O,ParseError<'a>>;
+
+/// Sets one of the reader's thread-locals (where we're reading from,  whether we're in a #(..),
+/// ..) for as long as it's held,  putting back what it was when dropped;  however we leave the
+/// parser that set it,  an early ? or a panicking data reader included 
+struct ReaderStateGuard<T: 'static> {
+    state: &'static LocalKey<RefCell<T>>,
+    previous: Option<T>
+}
+impl<T> ReaderStateGuard<T> {
+    fn set(state: &'static LocalKey<RefCell<T>>,value: T) -> ReaderStateGuard<T> {
+	ReaderStateGuard { state, previous: Some(state.with(|state| state.replace(value))) }
+    }
+    /// What our state was before we set it 
+    fn previous(&self) -> &T {
+	self.previous.as_ref().unwrap()
+    }
+}
+impl<T> Drop for ReaderStateGuard<T> {
+    fn drop(&mut self) {
+	if let Some(previous) = self.previous.take() {
+	    // Unless our thread is on its way out,  and our state with it 
+	    let _ = self.state.try_with(|state| state.replace(previous));
+	}
+    }
+}
+/// Sets the reader's state back to how it is outside of any read,  for as long as what we
+/// give back is held;  a form read on its own,  even by a data reader partway through reading
+/// another,  is read afresh 
+fn fresh_read_state() -> (ReaderStateGuard<bool>,ReaderStateGuard<bool>) {
+    (ReaderStateGuard::set(&IN_FN_LITERAL,false),ReaderStateGuard::set(&SUPPRESS_DATA_READERS,false))
+}
+
+/// Parses a literal token,  such as a delimiter,  along with any whitespace before it (our
+/// replacement for nom's deprecated ws!(tag!(..)) ).  Whatever comes after it is left to
+/// whoever reads next,  so nothing is skipped over (and no #_ form read) twice 
+fn ws_tag<'a>(token: &'static str) -> impl Fn(&'a [u8]) -> ReadResult<'a,&'a [u8]> {
+    preceded(whitespace,tag(token))
+}
+
+/// Skips over anything between forms that isn't itself a form;  whitespace (commas included),
+/// ; comments and #! lines,  which run to the end of the line,  and #_ discarded forms.  (A
+/// reader conditional without a branch for our features reads as nothing too,  but we'd have
+/// to read it to know,  so that's left to whoever reads it;  see reader_conditional)
+/// Example:
+///    , ;; a comment
+///    #_ (not read) #_ #_ :also :discarded
+fn whitespace(input: &[u8]) -> ReadResult<'_,()> {
+    let mut rest_input = input;
+    loop {
+	match rest_input {
+	    [byte,after_byte_input @ ..] if byte.is_ascii_whitespace() || *byte == b',' => {
+		rest_input = after_byte_input;
+	    },
+	    [b';',..] | [b'#',b'!',..] => {
+		let line_len = rest_input.iter().take_while(|byte| **byte != b'\n').count();
+		rest_input = &rest_input[line_len..];
+	    },
+	    // #_ #_ a b discards both a and b,  since reading the form the first #_ discards
+	    // skips over the second #_ and its form first 
+	    [b'#',b'_',discarded_input @ ..] => {
+		let (after_discarded_input,_) = read_form(discarded_input)?;
+		rest_input = after_discarded_input;
+	    },
+	    _ => return Ok((rest_input,()))
+	}
+    }
+}
+thread_local! {
+    // The namespace ::name keywords are resolved against;  *ns*,  as far as the reader is concerned 
+    static CURRENT_NS: RefCell<String> = RefCell::new(String::from("user"));
+}
+/// Sets the namespace ::name keywords read from here on are resolved against 
+// For whoever is embedding us;  we've no in-ns of our own to call this yet 
+#[allow(dead_code)]
+pub fn set_current_ns(ns: &str) {
+    CURRENT_NS.with(|current_ns| *current_ns.borrow_mut() = String::from(ns));
+}
+pub fn current_ns() -> String {
+    CURRENT_NS.with(|current_ns| current_ns.borrow().clone())
+}
+
+thread_local! {
+    // Where we look up the namespace an ::alias/name keyword's alias names;  weak,  as our
+    // environment is what holds on to us,  not the other way around 
+    static READER_ENVIRONMENT: RefCell<Weak<Environment>> = const { RefCell::new(Weak::new()) };
+}
+/// Sets the environment whose namespaces and aliases ::alias/name keywords are resolved in
+pub fn set_reader_environment(environment: &Rc<Environment>) {
+    READER_ENVIRONMENT.with(|reader_environment| *reader_environment.borrow_mut() = Rc::downgrade(environment));
+}
+/// The namespace ns names in our environment,  if any (see Environment::resolve_namespace) 
+fn resolve_namespace(ns: &Symbol) -> Option<Symbol> {
+    READER_ENVIRONMENT.with(|reader_environment| reader_environment.borrow().upgrade())
+	.and_then(|environment| environment.resolve_namespace(ns))
+}
 
 /// Parses valid Clojure identifiers
-/// Example Successes: ab,  cat,  -12+3, |blah|, <well>
+/// Example Successes: ab,  cat,  -12+3, |blah|, <well>, inc', x#, clojure.set/union
 /// Example Failures:  'a,  12b,   ,cat  
-pub fn identifier_parser(input:&[u8]) -> IResult<&[u8], String> {
+pub fn identifier_parser(input:&[u8]) -> ReadResult<'_,String> {
     named!( non_numeric_identifier_char<&[u8],u8>,
-	    alt!( map!(one_of!("|?<>+-_=^%&$*!"), |x| x as u8 ) |
-		  map!(take_while_m_n!(1,1,is_alphabetic),|ls| ls[0] as u8)));
+	    alt!( map!(one_of!("|?<>+-_=%&$*!/."), |x| x as u8 ) |
+		  map!(take_while_m_n!(1,1,is_alphabetic),|ls| ls[0])));
     named!( identifier_char<&[u8],u8>,
-	    alt!( map!(one_of!("|?<>+-_=^%&$*!"), |x| x as u8 ) |
-		  map!(take_while_m_n!(1,1,is_alphanumeric),|ls| ls[0] as u8)));
+	    alt!( map!(one_of!("|?<>+-_=%&$*!/.'#"), |x| x as u8 ) |
+		  map!(take_while_m_n!(1,1,is_alphanumeric),|ls| ls[0])));
     named!( identifier_ <&[u8],String> ,
 	    do_parse!(
 		head: non_numeric_identifier_char >>
@@ -50,26 +162,121 @@ pub fn identifier_parser(input:&[u8]) -> IResult<&[u8], String> {
 		(format!("{}{}",head as char,rest_input))
 	    ));
 
-    identifier_(input)
+    identifier_(input).map_err(nom::Err::convert)
+}
 
+/// Parses valid Clojure symbols,  whose name is a valid identifier;  name,  or ns/name
+/// Example Successes: a,  clojure.string/join,  foo.bar,  /,  clojure.core//
+/// Example Failures:  foo/,  /foo,  a/b/c 
+pub fn symbol_parser(input: &[u8]) -> ReadResult<'_,Symbol> { 
+    let (rest_input,name) = identifier_parser(input)?;
+    let symbol = Symbol::intern(&name);
+    if !symbol.is_valid() {
+	return Err(ParseError::invalid_token(input));
+    }
+    Ok((rest_input,symbol))
 }
 
-/// Parses valid Clojure symbols,  whose name is a valid identifier 
-pub fn symbol_parser(input: &[u8]) -> IResult<&[u8], Symbol> { 
-    identifier_parser(input).map(|(rest_input,name)| {
-	(rest_input, Symbol::intern(&name))
-    })
+/// Whether byte can be part of a number token;  we read the whole token,  and only then
+/// decide what sort of number it is (if it's one at all -- 12cat is not) 
+fn is_number_char(byte: u8) -> bool {
+    is_alphanumeric(byte) || byte == b'.' || byte == b'+' || byte == b'-' || byte == b'/'
+}
+
+/// Turns a number token into the number it's written as,  or None if it isn't one
+/// Example Successes:
+///    12     => Value::I64(12)
+///    -1.5   => Value::F64(-1.5)
+///    1e10   => Value::F64(10000000000.0)
+///    0xFF   => Value::I64(255)
+///    2r1010 => Value::I64(10)
+///    12N    => Value::BigInt(12)
+///    1/3    => Value::Ratio(1/3)
+///    4/2    => Value::I64(2)
+fn parse_number(token: &str) -> Option<Value> {
+    let (negative,unsigned) = match token.as_bytes().first() {
+	Some(b'-') => (true,&token[1..]),
+	Some(b'+') => (false,&token[1..]),
+	_ => (false,token)
+    };
+    // 1/3 
+    if let Some((numerator,denominator)) = unsigned.split_once('/') {
+	let numerator = parse_digits(numerator,10,negative)?;
+	let denominator = parse_digits(denominator,10,false)?;
+	if denominator.is_zero() {
+	    return None;
+	}
+	return Some(Number::from_ratio(BigRational::new(numerator,denominator)).demote().into_value());
+    }
+    // 2r1010,  36rZZ  (where an N is just another digit) 
+    if let Some(r_ind) = unsigned.find(['r','R']) {
+	let radix = unsigned[..r_ind].parse::<u32>().ok().filter(|radix| (2..=36).contains(radix))?;
+	return Some(integer_value(parse_digits(&unsigned[r_ind + 1..],radix,negative)?,false));
+    }
+    // 12N,  0xFFN;  a BigInt,  however small 
+    let (unsigned,is_bigint) = match unsigned.strip_suffix('N') {
+	Some(unsigned) => (unsigned,true),
+	None => (unsigned,false)
+    };
+    // 0xFF 
+    if let Some(hex_digits) = unsigned.strip_prefix("0x").or_else(|| unsigned.strip_prefix("0X")) {
+	return Some(integer_value(parse_digits(hex_digits,16,negative)?,is_bigint));
+    }
+    if unsigned.bytes().all(is_digit) {
+	return Some(integer_value(parse_digits(unsigned,10,negative)?,is_bigint));
+    }
+    if is_bigint {
+	return None;
+    }
+    // Rust would also happily parse inf and NaN here,  but our token starts with a digit
+    token.parse::<f64>().ok().map(Value::F64)
+}
+/// Parses unsigned digits in radix,  of any length 
+fn parse_digits(digits: &str,radix: u32,negative: bool) -> Option<BigInt> {
+    if digits.is_empty() || !digits.chars().all(|digit| digit.is_digit(radix)) {
+	return None;
+    }
+    let integer = BigInt::parse_bytes(digits.as_bytes(),radix)?;
+    Some(if negative { -integer } else { integer })
+}
+/// An integer literal is an i64,  unless it's too large to be one or was written as a BigInt 
+fn integer_value(integer: BigInt,is_bigint: bool) -> Value {
+    if is_bigint {
+	Value::BigInt(integer)
+    }
+    else {
+	Number::BigInt(integer).demote().into_value()
+    }
 }
 
-// @TODO add negatives 
-/// Parses valid integers
-/// Example Successes: 1, 2, 4153,  -12421
-pub fn integer(input: &[u8]) -> IResult<&[u8],i32> {
-    map_res(take_while1(is_digit),|digits: &[u8]| { 
-	String::from_utf8(digits.to_vec()).map(|digit_string| {
-	    digit_string.parse::<i32>().unwrap()
-	})
-    })(input)	
+/// Parses a number literal;  see parse_number 
+pub fn number_parser(input: &[u8]) -> ReadResult<'_,Value> {
+    // ##Inf,  ##-Inf,  ##NaN 
+    if let Ok((rest_input,_)) = tag::<&str,&[u8],ParseError>("##")(input) {
+	let (rest_input,name) = take_while1(is_number_char)(rest_input)?;
+	return match name {
+	    b"Inf" => Ok((rest_input,Value::F64(f64::INFINITY))),
+	    b"-Inf" => Ok((rest_input,Value::F64(f64::NEG_INFINITY))),
+	    b"NaN" => Ok((rest_input,Value::F64(f64::NAN))),
+	    _ => Err(ParseError::invalid_number(input))
+	};
+    }
+    // Numbers start with a digit,  perhaps after a sign;  otherwise, like -, +, or -> ,  we're a symbol 
+    let starts_number = match input {
+	[b'-',digit,..] | [b'+',digit,..] => is_digit(*digit),
+	[digit,..] => is_digit(*digit),
+	_ => false
+    };
+    if !starts_number {
+	return Err(nom::Err::Error((input,ErrorKind::Digit).into()));
+    }
+    let (rest_input,token) = take_while1(is_number_char)(input)?;
+    match std::str::from_utf8(token).ok().and_then(parse_number) {
+	Some(number) => Ok((rest_input,number)),
+	// Something like 12cat,  or a number too large for us;  not something else we
+	// should try reading this as 
+	None => Err(ParseError::invalid_number(input))
+    }
 }
 // Currently used to create 'try_readers', which are readers (or
 // reader functions, at least) that are basically composable InputType
@@ -78,23 +285,41 @@ pub fn integer(input: &[u8]) -> IResult<&[u8],i32> {
 /// Takes a parser, such as one that reads a &[u8] and returns an
 /// i32, and creates a new parser that instead returns a valid
 /// ClojureRS Value instead 
-pub fn to_value_parser<I,O: ToValue>(parser: impl Fn(I) -> IResult<I,O>) -> impl Fn(I) -> IResult<I,Value> {
-    move |input: I| parser(input).map(|(rest_input,thing)| (rest_input,thing.to_value()))
+pub fn to_value_parser<'a,O: ToValue>(parser: impl Fn(&'a [u8]) -> ReadResult<'a,O>) -> impl Fn(&'a [u8]) -> ReadResult<'a,Value> {
+    move |input: &'a [u8]| parser(input).map(|(rest_input,thing)| (rest_input,thing.to_value()))
+}
+
+/// Tries to parse &[u8] into a number;  Value::I64 or Value::F64
+/// Example Successes:
+///    1 => Value::I64(1),
+///    -5 => Value::I64(-5),
+///    1.5 => Value::F64(1.5),
+///    1e10 => Value::F64(10000000000.0),
+///    ##Inf => Value::F64(inf),
+///    0xFF => Value::I64(255),
+///    2r1010 => Value::I64(10)
+/// Example Failures:
+///    12cat,  1.2.3,  0xZZ,  1423152621625226126431525
+pub fn try_read_number(input: &[u8]) -> ReadResult<'_,Value> {
+    number_parser(input)
 }
 
-// @TODO make sure whitespace or 'nothing' is at the end, fail for
-// float like numbers 
-/// Tries to parse &[u8] into Value::I32
-/// Expects:
-///   Integers
+/// Tries to parse &[u8] into the literals Value::Boolean and Value::Nil,
+/// which would otherwise be read as symbols 
 /// Example Successes:
-///    1 => Value::I32(1),
-///    5 => Value::I32(5),
-///    1231415 => Value::I32(1231415)
+///    true  => Value::Boolean(true)
+///    false => Value::Boolean(false)
+///    nil   => Value::Nil
 /// Example Failures:
-///    1.5,  7.1321 , 1423152621625226126431525
-pub fn try_read_i32(input: &[u8]) -> IResult<&[u8],Value> {
-    to_value_parser(integer)(input)
+///    truest,  nil?,  False 
+pub fn try_read_bool_or_nil(input: &[u8]) -> ReadResult<'_,Value> {
+    let (rest_input,name) = identifier_parser(input)?;
+    match name.as_str() {
+	"true" => Ok((rest_input,Value::Boolean(true))),
+	"false" => Ok((rest_input,Value::Boolean(false))),
+	"nil" => Ok((rest_input,Value::Nil)),
+	_ => Err(nom::Err::Error((input,ErrorKind::Tag).into()))
+    }
 }
 
 /// Tries to parse &[u8] into Value::Symbol
@@ -104,72 +329,241 @@ pub fn try_read_i32(input: &[u8]) -> IResult<&[u8],Value> {
 ///    +common-lisp-global+ => Value::Symbol(Symbol { name: "+common-lisp-global+" })
 /// Example Failures:
 ///    12cat,  'quoted,  @at-is-for-references 
-pub fn try_read_symbol(input: &[u8]) -> IResult<&[u8],Value> {
+pub fn try_read_symbol(input: &[u8]) -> ReadResult<'_,Value> {
     to_value_parser(symbol_parser)(input)
 }
 
-// @TODO allow escaped strings 
+/// Tries to parse &[u8] into Value::Keyword
+/// Example Successes:
+///    :a      => Value::Keyword(:a)
+///    :ns/a   => Value::Keyword(:ns/a)
+///    ::a     => Value::Keyword(:user/a),  in the namespace user
+///    ::set/a => Value::Keyword(:clojure.set/a),  having aliased clojure.set as set
+/// Example Failures:
+///    a,  :,  ::nope/a,  with no namespace (or alias) nope
+pub fn try_read_keyword(input: &[u8]) -> ReadResult<'_,Value> {
+    let (rest_input,_) = tag(":")(input)?;
+    // ::name is resolved against the current namespace,  and ::alias/name against its aliases 
+    if let Ok((rest_input,_)) = tag::<&str,&[u8],ParseError>(":")(rest_input) {
+	let (rest_input,name) = identifier_parser(rest_input)?;
+	let sym = Symbol::intern(&name);
+	let ns = match &sym.ns {
+	    Some(alias) => match resolve_namespace(&Symbol::intern(alias)) {
+		Some(ns) => ns.to_string(),
+		None => return Err(ParseError::invalid_token(input))
+	    },
+	    None => current_ns()
+	};
+	return Ok((rest_input,Value::Keyword(Keyword::intern(&format!("{}/{}",ns,sym.name)))));
+    }
+    let (rest_input,name) = identifier_parser(rest_input)?;
+    Ok((rest_input,Value::Keyword(Keyword::intern(&name))))
+}
+
+/// Tries to parse &[u8] into Value::Char
+/// Example Successes:
+///    \a       => Value::Char('a')
+///    \newline => Value::Char('\n')
+///    \u03A9   => Value::Char('Ω')
+///    \Ω       => Value::Char('Ω')
+///    \o101    => Value::Char('A')
+///    \(       => Value::Char('(')
+/// Example Failures:
+///    \ab,  \u12,  \o400
+pub fn try_read_char(input: &[u8]) -> ReadResult<'_,Value> {
+    let (rest_input,_) = tag("\\")(input)?;
+    // Our first char can be anything at all (\( , \\ , \Ω ..),  but if it's alphanumeric,
+    // our literal runs on for as long as it stays alphanumeric (\newline, \u03A9 ..) 
+    let first_char_len = match rest_input.first() {
+	None => return Err(nom::Err::Incomplete(nom::Needed::Size(1))),
+	Some(byte) if byte.leading_ones() == 0 => 1,
+	Some(byte) => byte.leading_ones() as usize
+    };
+    if rest_input.len() < first_char_len {
+	return Err(ParseError::invalid_token(input));
+    }
+    let token_len = if is_alphanumeric(rest_input[0]) {
+	rest_input.iter().take_while(|byte| is_alphanumeric(**byte)).count()
+    }
+    else {
+	first_char_len
+    };
+    let (token,rest_input) = rest_input.split_at(token_len);
+    match std::str::from_utf8(token).ok().and_then(parse_char) {
+	Some(ch) => Ok((rest_input,Value::Char(ch))),
+	None => Err(ParseError::invalid_token(input))
+    }
+}
+/// Turns the token after a \ into the char it's written as,  or None if it isn't one 
+fn parse_char(token: &str) -> Option<char> {
+    let mut chars = token.chars();
+    if let (Some(ch),None) = (chars.next(),chars.next()) {
+	return Some(ch);
+    }
+    match token {
+	"newline" => Some('\n'),
+	"space" => Some(' '),
+	"tab" => Some('\t'),
+	"return" => Some('\r'),
+	"backspace" => Some('\u{8}'),
+	"formfeed" => Some('\u{c}'),
+	// \u03A9 
+	_ if token.len() == 5 && token.starts_with('u') => {
+	    u32::from_str_radix(&token[1..],16).ok().and_then(std::char::from_u32)
+	},
+	// \o101;  up to \o377 
+	_ if (2..=4).contains(&token.len()) && token.starts_with('o') => {
+	    u32::from_str_radix(&token[1..],8).ok().filter(|code| *code <= 0o377).and_then(std::char::from_u32)
+	},
+	_ => None
+    }
+}
+
 /// Tries to parse &[u8] into Value::String
 /// Example Successes:
 ///    "this is pretty straightforward" => Value::String("this is pretty straightforward")
-pub fn try_read_string(input: &[u8]) -> IResult<&[u8],Value> {
-    named!(quotation,
-	   ws!(tag!("\"")));
-    let (rest_input,_) = quotation(input)?;
-    to_value_parser(
-	map_res(
-	    terminated(
-		take_until("\""),
-		tag("\"")),
-	    |bytes: &[u8]| String::from_utf8(bytes.to_vec())))(rest_input)
+///    "say \"hi\"\n"                    => Value::String("say \"hi\"" followed by a newline)
+///    "\u03A9 is \101"                  => Value::String("Ω is A")
+/// Example Failures:
+///    "\q",  "\u12",  "\400" 
+pub fn try_read_string(input: &[u8]) -> ReadResult<'_,Value> {
+    let (quote_input,_) = whitespace(input)?;
+    let (mut rest_input,_) = tag("\"")(quote_input)?;
+    // Running out of input partway through,  we say so from where the string started 
+    let unterminated = || ParseError::failure(quote_input,ParseErrorKind::UnterminatedString);
+    // We collect bytes rather than chars,  and only decode them once we have the whole
+    // string,  so any UTF-8 in our input comes through as is 
+    let mut string_bytes = vec![];
+    loop {
+	match rest_input {
+	    // Our string may well continue on the next line 
+	    [] => return Err(unterminated()),
+	    [b'"',after_string_input @ ..] => {
+		return match String::from_utf8(string_bytes) {
+		    Ok(string) => Ok((after_string_input,Value::String(string))),
+		    Err(_) => Err(ParseError::invalid_token(quote_input))
+		};
+	    },
+	    [b'\\',escape_input @ ..] => {
+		// A bad escape is pointed out from its \ 
+		let (after_escape_input,ch) = string_escape(escape_input).map_err(|err| match err {
+		    nom::Err::Incomplete(_) => unterminated(),
+		    nom::Err::Error(_) | nom::Err::Failure(_) => ParseError::invalid_token(rest_input)
+		})?;
+		string_bytes.extend(ch.encode_utf8(&mut [0; 4]).as_bytes());
+		rest_input = after_escape_input;
+	    },
+	    [byte,after_byte_input @ ..] => {
+		string_bytes.push(*byte);
+		rest_input = after_byte_input;
+	    }
+	}
+    }
+}
+/// Parses what follows a backslash in a string into the character it escapes;
+/// n => newline,  u03A9 => Ω,  101 => A ..
+fn string_escape(input: &[u8]) -> ReadResult<'_,char> {
+    match input {
+	[] => Err(nom::Err::Incomplete(nom::Needed::Size(1))),
+	[b'"',rest_input @ ..] => Ok((rest_input,'"')),
+	[b'\\',rest_input @ ..] => Ok((rest_input,'\\')),
+	[b'n',rest_input @ ..] => Ok((rest_input,'\n')),
+	[b't',rest_input @ ..] => Ok((rest_input,'\t')),
+	[b'r',rest_input @ ..] => Ok((rest_input,'\r')),
+	[b'b',rest_input @ ..] => Ok((rest_input,'\u{8}')),
+	[b'f',rest_input @ ..] => Ok((rest_input,'\u{c}')),
+	// \uXXXX;  exactly four hex digits 
+	[b'u',rest_input @ ..] => {
+	    if rest_input.len() < 4 && rest_input.iter().all(|digit| digit.is_ascii_hexdigit()) {
+		return Err(nom::Err::Incomplete(nom::Needed::Size(4 - rest_input.len())));
+	    }
+	    if rest_input.len() < 4 {
+		return Err(ParseError::invalid_token(input));
+	    }
+	    let (digits,rest_input) = rest_input.split_at(4);
+	    std::str::from_utf8(digits).ok()
+		.filter(|digits| digits.bytes().all(|digit| digit.is_ascii_hexdigit()))
+		.and_then(|digits| u32::from_str_radix(digits,16).ok())
+		.and_then(std::char::from_u32)
+		.map(|ch| (rest_input,ch))
+		.ok_or(ParseError::invalid_token(input))
+	},
+	// \101;  one to three octal digits,  up to \377 
+	[b'0'..=b'7',..] => {
+	    let digit_count = input.iter().take(3).take_while(|byte| (b'0'..=b'7').contains(*byte)).count();
+	    let (digits,rest_input) = input.split_at(digit_count);
+	    let code = digits.iter().fold(0,|code,digit| code * 8 + u32::from(digit - b'0'));
+	    if code > 0o377 {
+		return Err(ParseError::invalid_token(input));
+	    }
+	    Ok((rest_input,std::char::from_u32(code).unwrap()))
+	},
+	_ => Err(ParseError::invalid_token(input))
+    }
 }
 
 // @TODO Perhaps generalize this, or even generalize it as a reader macro 
 /// Tries to parse &[u8] into Value::PersistentListMap, or some other Value::..Map   
 /// Example Successes:
 ///    {:a 1} => Value::PersistentListMap {PersistentListMap { MapEntry { :a, 1} .. ]})
-pub fn try_read_map(input: &[u8]) -> IResult<&[u8],Value> {
-    named!(lbracep,
-	   ws!(tag!("{")));
-    named!(rbracep,
-	   ws!(tag!("}")));
+/// Example Failures:
+///    {:a 1 :a 2},  as a map literal can't name the same key twice 
+pub fn try_read_map(input: &[u8]) -> ReadResult<'_,Value> {
+    let lbracep = ws_tag("{");
+    let rbracep = ws_tag("}");
     let (map_inner_input,_) = lbracep(input)?;
-    let mut map_as_vec : Vec<MapEntry> = vec![];
+    // We read our forms first,  and only then pair them up,  since a #?@(..) could splice in
+    // a key and value,  or a value for the key before it
+    let mut forms = vec![];
+    // The keys we've read so far,  so we can tell when one comes round again 
+    let mut keys = PersistentHashSet::empty();
     let mut rest_input = map_inner_input;
     loop {
-	let right_brace = rbracep(rest_input);
+	// We skip what's between our elements once,  here,  rather than in each parser we try 
+	let (element_input,_) = whitespace(rest_input)?;
+	let right_brace = rbracep(element_input);
 	match right_brace {
 	    Ok((after_map_input,_)) => {
+		if forms.len() % 2 != 0 {
+		    break Err(ParseError::invalid_token(input));
+		}
+		let map_as_vec = forms.chunks(2).map(|entry| MapEntry { key: Rc::clone(&entry[0]), val: Rc::clone(&entry[1]) }).collect::<Vec<MapEntry>>();
 		break Ok((after_map_input,map_as_vec.into_list_map().to_value()));
 	    },
 	    _ => {
-		let (_rest_input,next_key) = try_read(rest_input)?;
-		let (_rest_input,next_val) = try_read(_rest_input)?;
-		map_as_vec.push(MapEntry { key: Rc::new(next_key) , val:Rc::new(next_val)});
+		let (_rest_input,elements) = cut(read_elements)(element_input)?;
+		for element in elements.into_iter().map(Rc::new) {
+		    // Every other form is a key 
+		    if forms.len() % 2 == 0 {
+			if keys.contains(&element) {
+			    return Err(ParseError::failure(element_input,ParseErrorKind::DuplicateKey(element.to_string())));
+			}
+			keys = keys.conj(Rc::clone(&element));
+		    }
+		    forms.push(element);
+		}
 		rest_input = _rest_input;
 	    }
 	}
     }
 }
 
-// @TODO remove ws!, use nom functions in place of macro 
 /// Tries to parse &[u8] into Value::PersistentVector 
 /// Example Successes:
-///    [1 2 3] => Value::PersistentVector(PersistentVector { vals: [Rc(Value::I32(1) ... ]})
+///    [1 2 3] => Value::PersistentVector(PersistentVector { vals: [Rc(Value::I64(1) ... ]})
 ///    [1 2 [5 10 15] 3]
-///      => Value::PersistentVector(PersistentVector { vals: [Rc(Value::I32(1) .. Rc(Value::PersistentVector..)]})
-pub fn try_read_vector(input: &[u8]) -> IResult<&[u8],Value> {
-    named!(lbracketp,
-	   ws!(tag!("[")));
-    named!(rbracketp,
-	   ws!(tag!("]")));
+///      => Value::PersistentVector(PersistentVector { vals: [Rc(Value::I64(1) .. Rc(Value::PersistentVector..)]})
+pub fn try_read_vector(input: &[u8]) -> ReadResult<'_,Value> {
+    let lbracketp = ws_tag("[");
+    let rbracketp = ws_tag("]");
     let (vector_inner_input,_) = lbracketp(input)?;
     let mut vector_as_vec = vec![];
     // What's left of our input as we read more of our PersistentVector 
     let mut rest_input = vector_inner_input;
     loop {
+	let (element_input,_) = whitespace(rest_input)?;
 	// Try parse end of vector
-	let right_paren = rbracketp(rest_input);
+	let right_paren = rbracketp(element_input);
 	match right_paren {
 	    // If we succeeded,  we can convert our vector of values into a PersistentVector and return our success
 	    Ok((after_vector_input,_)) => {
@@ -177,16 +571,18 @@ pub fn try_read_vector(input: &[u8]) -> IResult<&[u8],Value> {
 	    },
 	    // Otherwise, we need to keep reading until we get that closing bracket letting us know we're finished
 	    _ => {
-		let next_form_parse = try_read(rest_input);
+		// Having read our opening delimiter,  we're the only thing this could be;  so if
+		// what's inside fails to read,  so do we, rather than letting another parser try 
+		let next_form_parse = cut(read_elements)(element_input);
 		match next_form_parse {
-		    // Normal behavior;  read our next element in the PersistentVector
-		    Ok((_rest_input,form)) => 	{
-			vector_as_vec.push(form.to_rc_value());
+		    // Normal behavior;  read our next element(s) in the PersistentVector
+		    Ok((_rest_input,elements)) => 	{
+			vector_as_vec.extend(elements.into_iter().map(Rc::new));
 			rest_input = _rest_input;
 		    },
 		    // This parse failed, return overall read failure 
-		    _ => {
-			break next_form_parse;
+		    Err(err) => {
+			break Err(err);
 		    }
 		}
 
@@ -195,31 +591,84 @@ pub fn try_read_vector(input: &[u8]) -> IResult<&[u8],Value> {
     }
 }
 
-pub fn try_read_list(input: &[u8]) -> IResult<&[u8],Value> {
-    named!(lparenp,
-	   ws!(tag!("(")));
-    named!(rparenp,
-	   ws!(tag!(")")));
+/// Tries to parse &[u8] into Value::PersistentHashSet 
+/// Example Successes:
+///    #{1 2 3} => Value::PersistentHashSet(..)
+/// Example Failures:
+///    #{1 2 1},  as a set literal can't name the same member twice 
+pub fn try_read_set(input: &[u8]) -> ReadResult<'_,Value> {
+    let lbracep = ws_tag("#{");
+    let rbracep = ws_tag("}");
+    let (set_inner_input,_) = lbracep(input)?;
+    let mut set = PersistentHashSet::empty();
+    let mut rest_input = set_inner_input;
+    loop {
+	let (member_input,_) = whitespace(rest_input)?;
+	if let Ok((after_set_input,_)) = rbracep(member_input) {
+	    break Ok((after_set_input,set.to_value()));
+	}
+	let (_rest_input,next_members) = cut(read_elements)(member_input)?;
+	for next_member in next_members.into_iter().map(Rc::new) {
+	    if set.contains(&next_member) {
+		return Err(ParseError::failure(member_input,ParseErrorKind::DuplicateKey(next_member.to_string())));
+	    }
+	    set = set.conj(next_member);
+	}
+	rest_input = _rest_input;
+    }
+}
+
+thread_local! {
+    // The source we're reading from right now,  if we know it,  which the lists we read say
+    // they came from 
+    static READING_SOURCE: RefCell<Option<Rc<Source>>> = const { RefCell::new(None) };
+}
+/// Like try_read,  for input that's a slice of source's text;  the lists read from it have
+/// their :file, :line and :column as metadata,  as does any error reading them.  Unlike
+/// try_read,  running out of forms to read is no error;  that's None 
+pub fn try_read_source<'a>(source: &Rc<Source>,input: &'a [u8]) -> Result<(&'a [u8],Option<Value>),ReaderError> {
+    let _read_state = fresh_read_state();
+    let _reading_source = ReaderStateGuard::set(&READING_SOURCE,Some(Rc::clone(source)));
+    read_next_form(input).map_err(|err| ReaderError::from_parse_error(source,input,err))
+}
+
+pub fn try_read_list(input: &[u8]) -> ReadResult<'_,Value> {
+    let rparenp = ws_tag(")");
     
-    let (list_inner_input,_) = lparenp(input)?;
+    let (list_input,_) = whitespace(input)?;
+    let (list_inner_input,_) = tag("(")(list_input)?;
+    // Where our ( is,  if we're reading from a source
+    let position = READING_SOURCE.with(|reading_source| {
+	reading_source.borrow().as_ref().and_then(|source| source.position_of(list_input))
+    });
     let mut list_as_vec = vec![];
     let mut rest_input = list_inner_input;
     loop {
-	let right_paren = rparenp(rest_input);
+	let (element_input,_) = whitespace(rest_input)?;
+	let right_paren = rparenp(element_input);
 	match right_paren {
 	    Ok((after_list_input,_)) => {
-		break Ok((after_list_input,list_as_vec.into_list().to_value()));
+		let list = list_as_vec.into_list().to_value();
+		// The empty list will never be a call that goes wrong,  so like Clojure we leave
+		// it without a position 
+		let list = match position {
+		    Some(position) if !matches!(list,Value::PersistentList(PersistentList::Empty(_))) => {
+			list.with_meta(Some(meta::merge_meta(list.meta(),&position.to_meta()))).unwrap_or(list)
+		    },
+		    _ => list
+		};
+		break Ok((after_list_input,list));
 	    },
 	    _ => {
-		let next_form_parse = try_read(rest_input);
+		let next_form_parse = cut(read_elements)(element_input);
 		match next_form_parse {
-		    Ok((_rest_input,form)) => 	{
-			list_as_vec.push(form.to_rc_value());
+		    Ok((_rest_input,elements)) => 	{
+			list_as_vec.extend(elements.into_iter().map(Rc::new));
 			rest_input = _rest_input;
 		    },
 		    // This parse failed, forward failure 
-		    _ => {
-			break next_form_parse;
+		    Err(err) => {
+			break Err(err);
 		    }
 		}
 
@@ -228,23 +677,1085 @@ pub fn try_read_list(input: &[u8]) -> IResult<&[u8],Value> {
     }
 }
 
-pub fn try_read(input: &[u8]) -> IResult<&[u8], Value> {
-    preceded(multispace0,alt(
+/// Tries to parse &[u8] into (quote form)
+/// Example Successes:
+///    'a      => (quote a)
+///    '(1 2)  => (quote (1 2))
+pub fn try_read_quote(input: &[u8]) -> ReadResult<'_,Value> {
+    let (rest_input,_) = tag("'")(input)?;
+    let (rest_input,form) = read_form(rest_input)?;
+    Ok((rest_input,special_form("quote",form)))
+}
+
+/// Tries to parse &[u8] into (unquote form) or (unquote-splicing form);  what syntax-quote
+/// looks for in the form it quotes 
+/// Example Successes:
+///    ~a   => (unquote a)
+///    ~@a  => (unquote-splicing a)
+pub fn try_read_unquote(input: &[u8]) -> ReadResult<'_,Value> {
+    let (rest_input,_) = tag("~")(input)?;
+    if let Ok((rest_input,_)) = tag::<&str,&[u8],ParseError>("@")(rest_input) {
+	let (rest_input,form) = read_form(rest_input)?;
+	return Ok((rest_input,special_form("unquote-splicing",form)));
+    }
+    let (rest_input,form) = read_form(rest_input)?;
+    Ok((rest_input,special_form("unquote",form)))
+}
+
+/// Tries to parse &[u8] into the form a syntax-quoted form expands into;  a form that
+/// evaluates to the quoted form,  with everything unquoted filled in
+/// Example Successes (in the namespace user):
+///    `a               => (quote user/a)
+///    `(a ~b)          => (concat [(quote user/a)] [b])
+///    `(do ~@body)     => (concat [(quote do)] body)
+///    `[x# ~x]         => [(quote x__12__auto__) x]
+/// Example Failures:
+///    `~@a
+pub fn try_read_syntax_quote(input: &[u8]) -> ReadResult<'_,Value> {
+    let (rest_input,_) = tag("`")(input)?;
+    let (rest_input,form) = read_form(rest_input)?;
+    match syntax_quote(&form,&mut HashMap::new()) {
+	Some(expansion) => Ok((rest_input,expansion)),
+	None => Err(ParseError::invalid_token(input))
+    }
+}
+/// (name form)
+fn special_form(name: &str,form: Value) -> Value {
+    vec![Symbol::intern(name).to_rc_value(),form.to_rc_value()].into_list().to_value()
+}
+/// Our special forms,  which a syntax-quote leaves as is rather than qualifying 
+const SPECIAL_FORMS : [&str; 16] = ["def","if","do","let","quote","fn","loop","recur","try","catch","finally",
+				    "throw","defmacro","handler-bind","restart-case","&"];
+/// If form is (name x),  x
+fn special_form_arg(name: &str,form: &Value) -> Option<Rc<Value>> {
+    match form {
+	Value::PersistentList(plist) if plist.len() == 2 => {
+	    let plist = Rc::new(plist.clone());
+	    match &*plist.nth(0) {
+		Value::Symbol(sym) if sym.name == name => Some(plist.nth(1)),
+		_ => None
+	    }
+	},
+	_ => None
+    }
+}
+/// The form `form expands into;  see try_read_syntax_quote.  Each foo# in a syntax-quote
+/// becomes the same generated symbol,  kept in gensyms.  None if we find a ~@ with no
+/// collection to splice into 
+fn syntax_quote(form: &Value,gensyms: &mut HashMap<String,Symbol>) -> Option<Value> {
+    match form {
+	Value::Symbol(sym) => {
+	    let quoted_sym = if let Some(prefix) = sym.name.strip_suffix('#') {
+		gensyms.entry(sym.name.clone()).or_insert_with(|| Symbol::intern(&format!("{}__auto__",Symbol::gensym(prefix)))).clone()
+	    }
+	    else if SPECIAL_FORMS.contains(&sym.name.as_str()) || sym.ns.is_some() {
+		sym.clone()
+	    }
+	    else {
+		Symbol::intern_with_ns(&current_ns(),&sym.name)
+	    };
+	    Some(special_form("quote",quoted_sym.to_value()))
+	},
+	Value::PersistentList(PersistentList::Empty(_)) => Some(special_form("quote",form.clone())),
+	Value::PersistentList(plist) => {
+	    if let Some(unquoted) = special_form_arg("unquote",form) {
+		return Some(unquoted.to_value());
+	    }
+	    if special_form_arg("unquote-splicing",form).is_some() {
+		return None;
+	    }
+	    let mut concat_args = vec![Symbol::intern_with_ns("clojure.core","concat").to_rc_value()];
+	    concat_args.extend(syntax_quote_elements(PersistentList::iter(&Rc::new(plist.clone())),gensyms)?);
+	    Some(concat_args.into_list().to_value())
+	},
+	Value::PersistentVector(pvector) => {
+	    let splices = pvector.vals.iter().any(|val| special_form_arg("unquote-splicing",val).is_some());
+	    // A vector evaluates each of its elements anyways,  so unless we're splicing, we can
+	    // just stay a vector 
+	    if !splices {
+		let vals = pvector.vals.iter().map(|val| syntax_quote(val,gensyms).map(|val| val.to_rc_value())).collect::<Option<Vec<Rc<Value>>>>()?;
+		return Some(vals.into_vector().to_value());
+	    }
+	    let mut concat_args = vec![Symbol::intern_with_ns("clojure.core","concat").to_rc_value()];
+	    concat_args.extend(syntax_quote_elements(pvector.vals.iter().cloned(),gensyms)?);
+	    Some(vec![Symbol::intern_with_ns("clojure.core","vec").to_rc_value(),concat_args.into_list().to_rc_value()].into_list().to_value())
+	},
+	// As does a set 
+	Value::PersistentHashSet(phashset) => {
+	    if !phashset.iter().any(|val| special_form_arg("unquote-splicing",&val).is_some()) {
+		let vals = phashset.iter().map(|val| syntax_quote(&val,gensyms).map(|val| val.to_rc_value())).collect::<Option<Vec<Rc<Value>>>>()?;
+		return Some(vals.into_iter().collect::<PersistentHashSet>().to_value());
+	    }
+	    let mut concat_args = vec![Symbol::intern_with_ns("clojure.core","concat").to_rc_value()];
+	    concat_args.extend(syntax_quote_elements(phashset.iter(),gensyms)?);
+	    Some(vec![Symbol::intern_with_ns("clojure.core","set").to_rc_value(),concat_args.into_list().to_rc_value()].into_list().to_value())
+	},
+	// Likewise a map evaluates its keys and values
+	// @TODO support ~@ in maps,  which needs something like (apply hash-map ..) 
+	Value::PersistentListMap(plistmap) => {
+	    let mut entries = vec![];
+	    for MapEntry { key, val } in plistmap.iter() {
+		entries.push(MapEntry { key: syntax_quote(&key,gensyms)?.to_rc_value(),
+					val: syntax_quote(&val,gensyms)?.to_rc_value() });
+	    }
+	    Some(entries.into_list_map().to_value())
+	},
+	// Anything else evaluates to itself anyways 
+	_ => Some(form.clone())
+    }
+}
+/// Each element as a collection for concat to join;  a ~@ element is one already,  and
+/// anything else becomes [element]
+fn syntax_quote_elements(elements: impl Iterator<Item = Rc<Value>>,gensyms: &mut HashMap<String,Symbol>) -> Option<Vec<Rc<Value>>> {
+    elements.map(|element| match special_form_arg("unquote-splicing",&element) {
+	Some(spliced) => Some(spliced),
+	None => Some(vec![syntax_quote(&element,gensyms)?.to_rc_value()].into_vector().to_rc_value())
+    }).collect()
+}
+
+thread_local! {
+    // Whether we're in the middle of reading a #(..);  they can't be nested 
+    static IN_FN_LITERAL: RefCell<bool> = const { RefCell::new(false) };
+}
+/// Tries to parse &[u8] into the fn an anonymous fn literal is short for;  %, or %1, is its
+/// first argument,  %2 its second, ..  and %& the rest 
+/// Example Successes:
+///    #(+ % 1)          => (fn [p1__1] (+ p1__1 1))
+///    #(f %2 %& %1)     => (fn [p1__2 p2__3 & rest__4] (f p2__3 rest__4 p1__2))
+/// Example Failures:
+///    #(+ % #(- %)),  #(%0)
+pub fn try_read_fn_literal(input: &[u8]) -> ReadResult<'_,Value> {
+    let (list_input,_) = tag("#")(input)?;
+    if !list_input.starts_with(b"(") {
+	return Err(nom::Err::Error((input,ErrorKind::Tag).into()));
+    }
+    let in_fn_literal = ReaderStateGuard::set(&IN_FN_LITERAL,true);
+    if *in_fn_literal.previous() {
+	return Err(ParseError::failure(input,ParseErrorKind::NestedFnLiteral));
+    }
+    let (rest_input,body) = try_read_list(list_input)?;
+    drop(in_fn_literal);
+
+    let mut args = FnLiteralArgs { positional: vec![], rest: None };
+    let body = match replace_arg_literals(&body,&mut args) {
+	Some(body) => body,
+	None => return Err(ParseError::invalid_token(input))
+    };
+    let mut params = args.positional.iter().map(|param| param.to_rc_value()).collect::<Vec<Rc<Value>>>();
+    if let Some(rest) = args.rest {
+	params.push(Symbol::intern("&").to_rc_value());
+	params.push(rest.to_rc_value());
+    }
+    Ok((rest_input,vec![Symbol::intern("fn").to_rc_value(),
+			params.into_vector().to_rc_value(),
+			body.to_rc_value()].into_list().to_value()))
+}
+/// The params an anonymous fn literal has needed so far 
+struct FnLiteralArgs {
+    positional: Vec<Symbol>,
+    rest: Option<Symbol>
+}
+/// form,  with each %, %n and %& replaced by the param it refers to;  None if we find an
+/// arg literal that isn't one of those (%0, %x ..) 
+fn replace_arg_literals(form: &Value,args: &mut FnLiteralArgs) -> Option<Value> {
+    match form {
+	Value::Symbol(sym) if sym.ns.is_none() && sym.name.starts_with('%') => {
+	    let param = match &sym.name[1..] {
+		"&" => args.rest.get_or_insert_with(|| Symbol::gensym("rest")).clone(),
+		n => {
+		    let n = if n.is_empty() { 1 } else { n.parse::<usize>().ok().filter(|n| *n > 0)? };
+		    // %3 alone still means our fn takes (and ignores) a first and second argument 
+		    while args.positional.len() < n {
+			args.positional.push(Symbol::gensym(&format!("p{}",args.positional.len() + 1)));
+		    }
+		    args.positional[n - 1].clone()
+		}
+	    };
+	    Some(param.to_value())
+	},
+	Value::PersistentList(plist) => {
+	    let elements = PersistentList::iter(&Rc::new(plist.clone()))
+		.map(|element| replace_arg_literals(&element,args).map(|element| element.to_rc_value()))
+		.collect::<Option<Vec<Rc<Value>>>>()?;
+	    Some(elements.into_list().to_value())
+	},
+	Value::PersistentVector(pvector) => {
+	    let elements = pvector.vals.iter()
+		.map(|element| replace_arg_literals(element,args).map(|element| element.to_rc_value()))
+		.collect::<Option<Vec<Rc<Value>>>>()?;
+	    Some(elements.into_vector().to_value())
+	},
+	Value::PersistentHashSet(phashset) => {
+	    let elements = phashset.iter()
+		.map(|element| replace_arg_literals(&element,args).map(|element| element.to_rc_value()))
+		.collect::<Option<Vec<Rc<Value>>>>()?;
+	    Some(elements.into_iter().collect::<PersistentHashSet>().to_value())
+	},
+	Value::PersistentListMap(plistmap) => {
+	    let mut entries = vec![];
+	    for MapEntry { key, val } in plistmap.iter() {
+		entries.push(MapEntry { key: replace_arg_literals(&key,args)?.to_rc_value(),
+					val: replace_arg_literals(&val,args)?.to_rc_value() });
+	    }
+	    Some(entries.into_list_map().to_value())
+	},
+	_ => Some(form.clone())
+    }
+}
+
+thread_local! {
+    // The features a reader conditional picks its branch by;  :rs being us,  ClojureRS 
+    static READER_FEATURES: RefCell<Vec<Keyword>> = RefCell::new(vec![Keyword::intern("rs")]);
+}
+/// Sets the features reader conditionals read from here on pick their branch by;  say,
+/// [:rs :server].  :default is always picked when nothing before it is 
+// For whoever is embedding us 
+#[allow(dead_code)]
+pub fn set_reader_features(features: Vec<Keyword>) {
+    READER_FEATURES.with(|reader_features| *reader_features.borrow_mut() = features);
+}
+pub fn reader_features() -> Vec<Keyword> {
+    READER_FEATURES.with(|reader_features| reader_features.borrow().clone())
+}
+/// Parses a reader conditional starting with prefix (#? or #?@) into the form of its first
+/// branch for one of our features,  or None if it has none.  The branches we don't pick are
+/// read without their tagged literals' data readers,  since they may well be for tags only
+/// the Clojure they're for knows.  This is the one place a conditional is read;  each is
+/// read once,  by whoever reads the form (or elements) it stands for
+///
+/// Unlike Clojure,  which only allows them in .cljc files,  we read reader conditionals
+/// wherever we read;  in .clj files,  at the REPL,  and in read-string.  This is deliberate;
+/// code shared with other Clojures is what they're for,  and it costs nothing to let it be
+/// shared from anywhere
+/// Example:
+///    #?(:clj 1 :rs 2)      => Some(2)
+///    #?(:clj 1)            => None
+///    #?(:cljs 1 :default 3) => Some(3)
+///    #?(:clj #java.io.File "a" :rs 4) => Some(4)
+fn reader_conditional<'a>(input: &'a [u8],prefix: &'static str) -> ReadResult<'a,Option<Value>> {
+    let (branches_input,_) = tag(prefix)(input)?;
+    if !branches_input.starts_with(b"(") {
+	return Err(ParseError::invalid_token(input));
+    }
+    let features = reader_features();
+    let mut picked = None;
+    let mut rest_input = &branches_input[1..];
+    loop {
+	let (feature_input,_) = whitespace(rest_input)?;
+	if let Ok((after_branches_input,_)) = ws_tag(")")(feature_input) {
+	    return Ok((after_branches_input,picked));
+	}
+	let (branch_input,feature) = cut(read_form)(feature_input)?;
+	let picks_branch = match &feature {
+	    Value::Keyword(feature) => picked.is_none() && (feature.full_name() == "default" || features.contains(feature)),
+	    _ => return Err(ParseError::invalid_token(feature_input))
+	};
+	let (branch_input,_) = whitespace(branch_input)?;
+	// A feature with no branch after it 
+	if branch_input.starts_with(b")") {
+	    return Err(ParseError::invalid_token(input));
+	}
+	let (after_branch_input,branch) = if picks_branch {
+	    cut(read_form)(branch_input)?
+	}
+	else {
+	    let _suppressing = ReaderStateGuard::set(&SUPPRESS_DATA_READERS,true);
+	    cut(read_form)(branch_input)?
+	};
+	if picks_branch {
+	    picked = Some(branch);
+	}
+	rest_input = after_branch_input;
+    }
+}
+/// Tries to parse &[u8] into the form a reader conditional picks for our features,  or if it
+/// has no branch for us,  the form after it
+/// Example Successes:
+///    #?(:clj (Math/abs x) :rs (abs x)) => (abs x)
+///    #?(:clj 1) 2                      => 2
+/// Example Failures:
+///    #?@(:rs [1 2]),  outside of a collection to splice into;   #?(:rs)
+pub fn try_read_reader_conditional(input: &[u8]) -> ReadResult<'_,Value> {
+    if !input.starts_with(b"#?(") {
+	return Err(nom::Err::Error((input,ErrorKind::Tag).into()));
+    }
+    match reader_conditional(input,"#?")? {
+	(rest_input,Some(form)) => Ok((rest_input,form)),
+	(rest_input,None) => read_form(rest_input)
+    }
+}
+/// Parses the next element(s) of a collection;  the next form,  the elements of the
+/// collection a #?@(..) picks,  spliced in,  or nothing at all for a reader conditional
+/// without a branch for us
+/// Example:
+///    [1 #?@(:rs [2 3]) 4]  => [1 2 3 4] 
+///    [1 #?(:clj 2)]        => [1]
+fn read_elements(input: &[u8]) -> ReadResult<'_,Vec<Value>> {
+    let (elements_input,_) = whitespace(input)?;
+    if elements_input.starts_with(b"#?(") {
+	let (rest_input,picked) = reader_conditional(elements_input,"#?")?;
+	return Ok((rest_input,picked.into_iter().collect()));
+    }
+    if !elements_input.starts_with(b"#?@") {
+	return read_form(elements_input).map(|(rest_input,form)| (rest_input,vec![form]));
+    }
+    let (rest_input,spliced) = reader_conditional(elements_input,"#?@")?;
+    match spliced {
+	None => Ok((rest_input,vec![])),
+	Some(Value::PersistentList(plist)) => Ok((rest_input,Rc::new(plist).iter().map(|form| form.to_value()).collect())),
+	Some(Value::PersistentVector(pvector)) => Ok((rest_input,pvector.vals.iter().map(|form| form.to_value()).collect())),
+	Some(_) => Err(ParseError::invalid_token(elements_input))
+    }
+}
+
+thread_local! {
+    // Whether we're reading a branch of a reader conditional we won't pick;  its tagged
+    // literals are read as just their forms,  without running their data readers 
+    static SUPPRESS_DATA_READERS: RefCell<bool> = const { RefCell::new(false) };
+}
+/// Tries to parse &[u8] into what the data reader for a tagged literal's tag makes of its
+/// form (see data_readers) 
+/// Example Successes:
+///    #inst "2020-01-01T00:00:00Z"  => Value::Inst(..)
+///    #uuid "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"  => Value::Uuid(..)
+/// Example Failures:
+///    #unknown/tag 1,  with no data reader for unknown/tag;   #inst "yesterday"
+pub fn try_read_tagged_literal(input: &[u8]) -> ReadResult<'_,Value> {
+    let (tag_input,_) = tag("#")(input)?;
+    if !tag_input.first().is_some_and(|byte| is_alphabetic(*byte)) {
+	return Err(nom::Err::Error((input,ErrorKind::Tag).into()));
+    }
+    let (form_input,tag) = cut(symbol_parser)(tag_input)?;
+    let (rest_input,form) = cut(read_form)(form_input)?;
+    if SUPPRESS_DATA_READERS.with(|suppress| *suppress.borrow()) {
+	return Ok((rest_input,form));
+    }
+    match data_readers::read_tagged(&tag,&form) {
+	None => Err(ParseError::failure(input,ParseErrorKind::UnknownTag(tag.to_string()))),
+	Some(Value::Condition(condition)) => {
+	    let literal = String::from_utf8_lossy(&input[..input.len() - rest_input.len()]).into_owned();
+	    Err(ParseError::failure(input,ParseErrorKind::InvalidTaggedLiteral(literal,Some(Rc::new(condition)))))
+	},
+	Some(value) => Ok((rest_input,value))
+    }
+}
+
+/// Tries to parse &[u8] into a form carrying the metadata before it;  a map of metadata, or
+/// shorthand for one 
+/// Example Successes:
+///    ^{:doc "Adds one"} inc  => inc,  with the metadata {:doc "Adds one"}
+///    ^:private x             => x,  with {:private true}
+///    ^String s,  ^"String" s => s,  with {:tag String}
+///    ^:a ^:b x               => x,  with {:a true :b true}
+/// Example Failures:
+///    ^:private 1,  as a number can't carry metadata;   ^1 x 
+pub fn try_read_meta(input: &[u8]) -> ReadResult<'_,Value> {
+    let (meta_input,_) = tag("^")(input)?;
+    let (form_input,meta) = read_form(meta_input)?;
+    let meta = match meta {
+	Value::PersistentListMap(meta) => meta,
+	Value::Keyword(_) => vec![MapEntry { key: meta.to_rc_value(), val: Value::Boolean(true).to_rc_value() }].into_list_map(),
+	Value::Symbol(_) | Value::String(_) => vec![MapEntry { key: Keyword::intern("tag").to_rc_value(), val: meta.to_rc_value() }].into_list_map(),
+	_ => return Err(ParseError::invalid_token(meta_input))
+    };
+    let (rest_input,form) = read_form(form_input)?;
+    match form.with_meta(Some(meta::merge_meta(form.meta(),&meta))) {
+	Some(form) => Ok((rest_input,form)),
+	None => Err(ParseError::invalid_token(form_input))
+    }
+}
+
+/// Reads the next form out of input,  returning it and the input left after it
+/// Example Successes:
+///    (+ 1 2) 3   => ((+ 1 2), " 3")
+/// Example Failures:
+///    (1 2]  => ReaderError::UnbalancedDelimiter { delimiter: ']', .. }
+///    (1 2   => ReaderError::UnexpectedEof { .. }
+pub fn try_read(input: &[u8]) -> Result<(&[u8],Value),ReaderError> {
+    let _read_state = fresh_read_state();
+    let _reading_source = ReaderStateGuard::set(&READING_SOURCE,None);
+    // Without a source to say where input is from,  we say where in input itself we gave up 
+    read_form(input).map_err(|err| {
+	let source = Source::new("NO_SOURCE_FILE",&String::from_utf8_lossy(input));
+	let source_input = &source.text.as_bytes()[..input.len().min(source.text.len())];
+	// err's input is a slice of input;  the same slice of our source's text 
+	let source_slice = |err_input: &[u8]| {
+	    let offset = err_input.as_ptr() as usize - input.as_ptr() as usize;
+	    &source_input[offset.min(source_input.len())..(offset + err_input.len()).min(source_input.len())]
+	};
+	let err = err.map(|err| ParseError { input: source_slice(err.input), ..err });
+	ReaderError::from_parse_error(&source,source_input,err)
+    })
+}
+/// The next form in input,  or None if there's nothing left in it but whitespace,  comments,
+/// discarded forms and reader conditionals without a branch for us 
+fn read_next_form(input: &[u8]) -> ReadResult<'_,Option<Value>> {
+    let mut rest_input = input;
+    loop {
+	let (form_input,_) = whitespace(rest_input)?;
+	if form_input.is_empty() {
+	    return Ok((form_input,None));
+	}
+	if !form_input.starts_with(b"#?(") {
+	    return read_form(form_input).map(|(rest_input,form)| (rest_input,Some(form)));
+	}
+	match reader_conditional(form_input,"#?")? {
+	    (rest_input,Some(form)) => return Ok((rest_input,Some(form))),
+	    (after_conditional_input,None) => rest_input = after_conditional_input
+	}
+    }
+}
+/// The nom parser behind try_read,  which our parsers use to read the forms inside them.
+/// Whatever none of our parsers can read is no form at all;  we've either run out of input,
+/// reached a delimiter with nothing open for it to close,  or found a token we can't read 
+fn read_form(input: &[u8]) -> ReadResult<'_,Value> {
+    let (form_input,_) = whitespace(input)?;
+    let read = alt(
 	(try_read_map,
 	 try_read_string,
+	 try_read_number,
+	 try_read_keyword,
+	 try_read_char,
+	 try_read_bool_or_nil,
 	 try_read_symbol,
-	 try_read_i32,
 	 try_read_list,
-	 try_read_vector)))(input)
+	 try_read_vector,
+	 try_read_quote,
+	 try_read_syntax_quote,
+	 try_read_unquote,
+	 try_read_fn_literal,
+	 try_read_set,
+	 try_read_reader_conditional,
+	 try_read_tagged_literal,
+	 try_read_meta))(form_input);
+    match read {
+	Err(nom::Err::Error(_)) => Err(match form_input {
+	    [] => ParseError::failure(form_input,ParseErrorKind::UnexpectedEof),
+	    [delimiter @ (b')' | b']' | b'}'),..] => ParseError::failure(form_input,ParseErrorKind::UnbalancedDelimiter(*delimiter as char)),
+	    _ => ParseError::invalid_token(form_input)
+	}),
+	read => read
+    }
 }
 
-pub fn debug_try_read(input: &[u8]) -> IResult<&[u8], Value> {
-    
-    let reading = try_read(input);
-    match &reading {
-	Ok((_,value)) => println!("Reading: {}",value),
-	_ => println!("Reading: {:?}",reading)
-    };
-    reading
+/// Reads forms one at a time out of input,  reading more lines of it only as a form needs
+/// them;  a form can go on for as many lines as it likes.  Each is read as from a source
+/// named name,  so the lists we read say where they came from.  We only read a form once all
+/// of it could be there (see FormScan),  and read it just the once
+/// Example:
+///    for form in LispReader::new("core.clj",BufReader::new(file)) { .. }
+pub struct LispReader<R: BufRead> {
+    input: R,
+    name: String,
+    // What we've read from input that we might still need,  newlines and all;  from the
+    // start of the line the form we're reading (or last read) starts on 
+    text: String,
+    // The line our text starts on 
+    first_line: usize,
+    // How much of our text we've read forms out of 
+    consumed: usize,
+    // Our text as a Source,  built when we first read from it since it last changed 
+    source: Option<Rc<Source>>,
+    scan: FormScan,
+    // Where the last form we tried reading before all of it was there seemed to end;  we
+    // don't try again until another form ends past it 
+    retry_after: usize,
+    at_eof: bool
+}
+impl<R: BufRead> LispReader<R> {
+    pub fn new(name: &str,input: R) -> LispReader<R> {
+	LispReader {
+	    input,
+	    name: String::from(name),
+	    text: String::new(),
+	    first_line: 1,
+	    consumed: 0,
+	    source: None,
+	    scan: FormScan::default(),
+	    retry_after: 0,
+	    at_eof: false
+	}
+    }
+    /// Reads another line onto our text,  noting if there's none left to read
+    // @TODO say when input gives us an io::Error, rather than treating it as the end of input 
+    fn read_line(&mut self) {
+	match self.input.read_line(&mut self.text) {
+	    Ok(0) | Err(_) => self.at_eof = true,
+	    Ok(_) => {
+		self.scan.scan(self.text.as_bytes());
+		self.source = None;
+	    }
+	}
+    }
+    /// Lets go of the lines we've finished reading forms out of,  so we only ever hold on to
+    /// (and build a Source over) the text of the form we're reading 
+    fn drop_consumed_lines(&mut self) {
+	// The whitespace after the last form we read is as good as read too 
+	let rest = self.text[self.consumed..].trim_start_matches(|ch: char| ch.is_ascii_whitespace() || ch == ',');
+	self.consumed = self.text.len() - rest.len();
+	if let Some(newline_ind) = self.text[..self.consumed].rfind('\n') {
+	    self.first_line += self.text[..=newline_ind].matches('\n').count();
+	    self.text.drain(..=newline_ind);
+	    self.consumed -= newline_ind + 1;
+	    self.scan.shift(newline_ind + 1);
+	    self.retry_after = self.retry_after.saturating_sub(newline_ind + 1);
+	    self.source = None;
+	}
+    }
+    /// Whether a form we've yet to read may have ended by now 
+    fn form_may_have_ended(&self) -> bool {
+	self.scan.last_form_end > self.consumed.max(self.retry_after)
+    }
+}
+impl<R: BufRead> Iterator for LispReader<R> {
+    type Item = Result<Value,ReaderError>;
+    fn next(&mut self) -> Option<Self::Item> {
+	self.drop_consumed_lines();
+	loop {
+	    if self.at_eof || self.form_may_have_ended() {
+		if self.source.is_none() {
+		    self.source = Some(Source::starting_at_line(&self.name,&self.text,self.first_line));
+		}
+		let source = Rc::clone(self.source.as_ref().unwrap());
+		match try_read_source(&source,&source.text.as_bytes()[self.consumed..]) {
+		    Ok((rest_input,form)) => {
+			self.consumed = self.text.len() - rest_input.len();
+			if form.is_some() || self.at_eof {
+			    return form.map(Ok);
+			}
+		    },
+		    // The rest of our form may well be on the next line;  a ' at the end of this
+		    // one,  say 
+		    Err(err) if err.is_eof() && !self.at_eof => self.retry_after = self.scan.last_form_end,
+		    // Whatever else we'd read on this line,  we can't trust where it starts now 
+		    Err(err) => {
+			self.consumed = self.text.len();
+			self.scan = FormScan { scanned: self.text.len(), ..FormScan::default() };
+			return Some(Err(err));
+		    }
+		}
+	    }
+	    self.read_line();
+	}
+    }
+}
+/// How much of a LispReader's text we've looked over for where its forms end;  just its
+/// delimiters,  strings,  comments and tokens,  which is enough to tell when a form might be
+/// whole without reading it.  Reading a form at every line of it instead would read a form
+/// n lines long n times over
+#[derive(Debug,Default)]
+struct FormScan {
+    // How much of our text we've looked over 
+    scanned: usize,
+    // How many (, [ and { we're inside of 
+    depth: usize,
+    in_string: bool,
+    in_comment: bool,
+    // Just after a \,  in a string or (as a char literal) out of one 
+    escaped: bool,
+    // Where the token we're partway through,  if we are,  starts 
+    token_start: Option<usize>,
+    // Where the last form outside of any delimiters ends 
+    last_form_end: usize
+}
+impl FormScan {
+    /// Looks over the rest of text,  which is what we've looked over so far with more after it 
+    fn scan(&mut self,text: &[u8]) {
+	for (ind,byte) in text.iter().enumerate().skip(self.scanned) {
+	    if self.in_comment {
+		self.in_comment = *byte != b'\n';
+	    }
+	    else if self.in_string {
+		match byte {
+		    _ if self.escaped => self.escaped = false,
+		    b'\\' => self.escaped = true,
+		    b'"' => {
+			self.in_string = false;
+			self.end_form(ind + 1);
+		    },
+		    _ => {}
+		}
+	    }
+	    else if self.escaped {
+		self.escaped = false;
+	    }
+	    else {
+		match byte {
+		    b'\\' => {
+			self.escaped = true;
+			self.token_start.get_or_insert(ind);
+		    },
+		    b'!' if self.token_start.is_some_and(|start| &text[start..ind] == b"#") => {
+			self.token_start = None;
+			self.in_comment = true;
+		    },
+		    b';' => {
+			self.end_token(ind);
+			self.in_comment = true;
+		    },
+		    // Whatever's right before an opening delimiter (a #,  a ',  a ^:private) is
+		    // part of the same form 
+		    b'(' | b'[' | b'{' => {
+			self.token_start = None;
+			self.depth += 1;
+		    },
+		    b'"' => {
+			self.token_start = None;
+			self.in_string = true;
+		    },
+		    // One with nothing to close is still the end of something;  whoever reads it
+		    // will say it's unmatched 
+		    b')' | b']' | b'}' => {
+			self.end_token(ind);
+			self.depth = self.depth.saturating_sub(1);
+			self.end_form(ind + 1);
+		    },
+		    _ if byte.is_ascii_whitespace() || *byte == b',' => self.end_token(ind),
+		    _ => {
+			self.token_start.get_or_insert(ind);
+		    }
+		}
+	    }
+	}
+	self.scanned = text.len();
+    }
+    fn end_token(&mut self,ind: usize) {
+	if self.token_start.take().is_some() {
+	    self.end_form(ind);
+	}
+    }
+    fn end_form(&mut self,end: usize) {
+	if self.depth == 0 {
+	    self.last_form_end = end;
+	}
+    }
+    /// For when the first len bytes of our text are let go of 
+    fn shift(&mut self,len: usize) {
+	self.scanned -= len;
+	self.last_form_end = self.last_form_end.saturating_sub(len);
+	self.token_start = self.token_start.map(|start| start.saturating_sub(len));
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use crate::reader::*;
+    use crate::symbol::Symbol;
+    use crate::keyword::Keyword;
+    use crate::inst::Inst;
+    use crate::value::ToValue;
+
+    #[test]
+    fn test_read_symbols()
+    {
+	let read_symbol = |input: &str| match try_read(input.as_bytes()).unwrap().1 {
+	    Value::Symbol(sym) => (sym.ns.clone(),sym.name.clone()),
+	    read => panic!("Expected a symbol, got: {}",read)
+	};
+	assert_eq!(read_symbol("clojure.string/join"),(Some(String::from("clojure.string")),String::from("join")));
+	assert_eq!(read_symbol("foo.bar"),(None,String::from("foo.bar")));
+	assert_eq!(read_symbol("/"),(None,String::from("/")));
+	assert_eq!(read_symbol("clojure.core//"),(Some(String::from("clojure.core")),String::from("/")));
+	assert_eq!(try_read(b"set/union").unwrap().1.to_string(),"set/union");
+	for input in ["foo/","/foo","a/b/c"].iter() {
+	    assert!(matches!(try_read(input.as_bytes()),Err(ReaderError::InvalidToken { .. })),"{}",input);
+	}
+    }
+    #[test]
+    fn test_read_bool_and_nil()
+    {
+	assert_eq!(try_read(b"true").unwrap().1,Value::Boolean(true));
+	assert_eq!(try_read(b"false").unwrap().1,Value::Boolean(false));
+	assert_eq!(try_read(b"nil").unwrap().1,Value::Nil);
+	// Only the exact literals;  anything longer is still a symbol 
+	assert_eq!(try_read(b"truest").unwrap().1,Value::Symbol(Symbol::intern("truest")));
+	assert_eq!(try_read(b"nil?").unwrap().1,Value::Symbol(Symbol::intern("nil?")));
+    }
+
+    #[test]
+    fn test_read_keywords()
+    {
+	assert_eq!(try_read(b":a").unwrap().1,Value::Keyword(Keyword::intern("a")));
+	assert_eq!(try_read(b":ns/a").unwrap().1,Value::Keyword(Keyword::intern("ns/a")));
+	assert_eq!(try_read(b"::a").unwrap().1,Value::Keyword(Keyword::intern("user/a")));
+	assert!(try_read(b"::ns/a").is_err());
+	// ::alias/name is resolved against our environment's aliases 
+	let environment = Rc::new(Environment::new_main_environment());
+	environment.add_alias(Symbol::intern("set"),Symbol::intern("clojure.set"));
+	set_reader_environment(&environment);
+	assert_eq!(try_read(b"::set/foo").unwrap().1,Value::Keyword(Keyword::intern("clojure.set/foo")));
+	assert_eq!(try_read(b"::user/foo").unwrap().1,Value::Keyword(Keyword::intern("user/foo")));
+	assert!(matches!(try_read(b"::nope/foo"),Err(ReaderError::InvalidToken { .. })));
+	assert_eq!(try_read(b"{:a 1}").unwrap().1.to_string(),"{:a 1}");
+    }
+
+    #[test]
+    fn test_read_strings()
+    {
+	let read_string = |input: &str| match try_read(input.as_bytes()) {
+	    Ok((_,Value::String(string))) => string,
+	    read => panic!("Expected a string from {}, got: {:?}",input,read)
+	};
+	assert_eq!(read_string(r#""plain""#),"plain");
+	assert_eq!(read_string(r#""say \"hi\"""#),"say \"hi\"");
+	assert_eq!(read_string(r#""a\\b\n\t\r\b\f""#),"a\\b\n\t\r\u{8}\u{c}");
+	assert_eq!(read_string(r#""\u03A9 is \101, \0 \377""#),"Ω is A, \0 \u{ff}");
+	assert_eq!(read_string("\"Ωmega ∑ 😀\""),"Ωmega ∑ 😀");
+	assert!(try_read(br#""\q""#).is_err());
+	assert!(try_read(br#""\u12zz""#).is_err());
+	assert!(try_read(br#""\400""#).is_err());
+	assert!(matches!(try_read(br#""unterminated"#),Err(ReaderError::UnterminatedString { .. })));
+	// What we print readably,  we read back as is 
+	for string in ["say \"hi\"","\\n is not \n","tab\there","Ω\u{8}\u{c}\r"].iter() {
+	    let printed = Value::String(String::from(*string)).to_string_explicit();
+	    assert_eq!(read_string(&printed),*string);
+	}
+    }
+
+    #[test]
+    fn test_read_chars()
+    {
+	assert_eq!(try_read(b"\\a").unwrap().1,Value::Char('a'));
+	assert_eq!(try_read(b"\\newline").unwrap().1,Value::Char('\n'));
+	assert_eq!(try_read(b"\\space").unwrap().1,Value::Char(' '));
+	assert_eq!(try_read(b"\\tab").unwrap().1,Value::Char('\t'));
+	assert_eq!(try_read(b"\\u03A9").unwrap().1,Value::Char('Ω'));
+	assert_eq!(try_read("\\Ω".as_bytes()).unwrap().1,Value::Char('Ω'));
+	assert_eq!(try_read(b"\\o101").unwrap().1,Value::Char('A'));
+	assert_eq!(try_read(b"\\(").unwrap().1,Value::Char('('));
+	assert_eq!(try_read(b"[\\a \\b]").unwrap().1.to_string(),"[\\a \\b]");
+	assert!(try_read(b"\\ab").is_err());
+	assert!(try_read(b"\\u12").is_err());
+	assert!(try_read(b"\\o400").is_err());
+	// What we print readably,  we read back as is 
+	for ch in ['a','\n',' ','\t','\r','Ω','\\','('].iter() {
+	    assert_eq!(try_read(Value::Char(*ch).to_string_explicit().as_bytes()).unwrap().1,Value::Char(*ch));
+	}
+    }
+
+    #[test]
+    fn test_read_comments_and_discards()
+    {
+	assert_eq!(try_read(b"; a comment\n  ;; another\n 1").unwrap().1,Value::I64(1));
+	assert_eq!(try_read(b"#!/usr/bin/env clojure-rs\n:a").unwrap().1,Value::Keyword(Keyword::intern("a")));
+	assert_eq!(try_read(b"[1, 2,3 ,4]").unwrap().1.to_string(),"[1 2 3 4]");
+	assert_eq!(try_read(b"(a ; comment )\n b)").unwrap().1.to_string(),"(a b)");
+	assert_eq!(try_read(b"#_ 1 2").unwrap().1,Value::I64(2));
+	assert_eq!(try_read(b"[#_(a (b)) c #_d]").unwrap().1.to_string(),"[c]");
+	assert_eq!(try_read(b"(#_ #_ a b c)").unwrap().1.to_string(),"(c)");
+	assert_eq!(try_read(b"#_#_#_ 1 2 3 4").unwrap().1,Value::I64(4));
+	// Our discarded form may still be on its way 
+	assert!(matches!(try_read(b"#_ "),Err(ReaderError::UnexpectedEof { .. })));
+    }
+
+    #[test]
+    fn test_read_quotes()
+    {
+	let read_str = |input: &str| try_read(input.as_bytes()).unwrap().1.to_string();
+	assert_eq!(read_str("'a"),"(quote a)");
+	assert_eq!(read_str("'(1 'b)"),"(quote (1 (quote b)))");
+	assert_eq!(read_str("~a"),"(unquote a)");
+	assert_eq!(read_str("~@a"),"(unquote-splicing a)");
+	assert_eq!(read_str("`a"),"(quote user/a)");
+	assert_eq!(read_str("`ns/a"),"(quote ns/a)");
+	assert_eq!(read_str("`(def ~a :b 1)"),"(clojure.core/concat [(quote def)] [a] [:b] [1])");
+	assert_eq!(read_str("`(f ~@args)"),"(clojure.core/concat [(quote user/f)] args)");
+	assert_eq!(read_str("`[a ~b]"),"[(quote user/a) b]");
+	assert_eq!(read_str("`[a ~@b]"),"(clojure.core/vec (clojure.core/concat [(quote user/a)] b))");
+	// Each x# in a syntax-quote is the same symbol,  but a different one from the next syntax-quote's 
+	let gensyms = |input: &str| match try_read(input.as_bytes()).unwrap().1 {
+	    Value::PersistentVector(pvector) => pvector.vals.iter().map(|val| val.to_string()).collect::<Vec<String>>(),
+	    read => panic!("Expected a vector, got: {}",read)
+	};
+	let first = gensyms("`[x# x#]");
+	assert_eq!(first[0],first[1]);
+	assert!(first[0].ends_with("__auto__)"));
+	assert_ne!(first,gensyms("`[x# x#]"));
+	assert!(try_read(b"`~@a").is_err());
+    }
+
+    #[test]
+    fn test_read_fn_literals()
+    {
+	let read_fn = |input: &str| match try_read(input.as_bytes()).unwrap().1 {
+	    Value::PersistentList(plist) => PersistentList::iter(&Rc::new(plist)).map(|form| form.to_string()).collect::<Vec<String>>(),
+	    read => panic!("Expected a fn form, got: {}",read)
+	};
+	let fn_form = read_fn("#(+ % %1 1)");
+	assert_eq!(fn_form[0],"fn");
+	let param = fn_form[1].trim_matches(|ch| ch == '[' || ch == ']');
+	assert!(param.starts_with("p1__"));
+	assert_eq!(fn_form[2],format!("(+ {} {} 1)",param,param));
+	// %2 alone still takes two args;  %& takes the rest 
+	let fn_form = read_fn("#(list %2 %&)");
+	let params = fn_form[1].trim_matches(|ch| ch == '[' || ch == ']').split(' ').collect::<Vec<&str>>();
+	assert_eq!(params.len(),4);
+	assert_eq!(params[2],"&");
+	assert_eq!(fn_form[2],format!("(list {} {})",params[1],params[3]));
+	assert_eq!(read_fn("#(rand)")[1],"[]");
+	assert_eq!(try_read(b"#(#(+ %))").unwrap_err().to_string(),"Nested #()s are not allowed");
+	assert!(matches!(try_read(b"#(+ % #(- %))"),Err(ReaderError::NestedFnLiteral { .. })));
+	assert!(try_read(b"#(+ %0)").is_err());
+	// Having failed on a nested #(),  we can still read the next one 
+	assert_eq!(read_fn("#(inc %)")[0],"fn");
+    }
+
+    #[test]
+    fn test_read_maps()
+    {
+	assert_eq!(try_read(b"{:a 1 :b 2}").unwrap().1,try_read(b"{:b 2 :a 1}").unwrap().1);
+	assert_eq!(try_read(b"{:a 1 :a 2}").unwrap_err().to_string(),"Duplicate key: :a");
+	// Only keys need be distinct 
+	assert!(try_read(b"{:a 1 :b 1}").is_ok());
+	assert!(matches!(try_read(b"{:a #?@(:rs [1 :a]) 2}"),Err(ReaderError::DuplicateKey { .. })));
+    }
+
+    #[test]
+    fn test_read_sets()
+    {
+	assert_eq!(try_read(b"#{1 :a \"b\"} ").unwrap().1.to_string_explicit(),"#{1 :a \"b\"}");
+	assert_eq!(try_read(b"#{}").unwrap().1.to_string(),"#{}");
+	assert_eq!(try_read(b"#{1 2}").unwrap().1,try_read(b"#{2 1}").unwrap().1);
+	assert!(matches!(try_read(b"#{1 2 1}"),Err(ReaderError::DuplicateKey { .. })));
+	assert_eq!(try_read(b"#{1 2 1}").unwrap_err().to_string(),"Duplicate key: 1");
+	assert_eq!(try_read(b"#{##NaN ##NaN}").unwrap_err().to_string(),"Duplicate key: ##NaN");
+	assert!(matches!(try_read(b"#{1 2"),Err(ReaderError::UnexpectedEof { .. })));
+    }
+
+    #[test]
+    fn test_read_meta()
+    {
+	let read_meta = |input: &str| try_read(input.as_bytes()).unwrap().1.meta().unwrap().to_string();
+	assert_eq!(read_meta("^{:doc \"Adds one\"} inc"),"{:doc \"Adds one\"}");
+	assert_eq!(read_meta("^:private x"),"{:private true}");
+	assert_eq!(read_meta("^String [s]"),"{:tag String}");
+	assert_eq!(read_meta("^\"String\" (s)"),"{:tag \"String\"}");
+	assert_eq!(read_meta("^:m ()"),"{:m true}");
+	assert_eq!(read_meta("^:m {}"),"{:m true}");
+	let meta = try_read(b"^:a ^{:b 1 :a false} #{}").unwrap().1.meta().unwrap().to_value();
+	assert_eq!(meta,try_read(b"{:a true :b 1}").unwrap().1);
+	// Metadata doesn't change what we read 
+	assert_eq!(try_read(b"^:private x").unwrap().1,Symbol::intern("x").to_value());
+	assert!(matches!(try_read(b"^:private 1"),Err(ReaderError::InvalidToken { .. })));
+	assert!(matches!(try_read(b"^1 x"),Err(ReaderError::InvalidToken { .. })));
+	assert!(matches!(try_read(b"^:private"),Err(ReaderError::UnexpectedEof { .. })));
+    }
+    #[test]
+    fn test_read_source_positions()
+    {
+	let source = Source::new("positions.clj","(a)\n  ^:m (b\n   (c) ())\n(d");
+	let (rest_input,a) = try_read_source(&source,source.text.as_bytes()).unwrap();
+	let (rest_input,b) = try_read_source(&source,rest_input).unwrap();
+	let (a,b) = (a.unwrap(),b.unwrap());
+	assert_eq!(a.meta().unwrap().to_string(),"{:file \"positions.clj\", :line 1, :column 1}");
+	// Our own metadata rides along with our position 
+	assert_eq!(b.meta().unwrap().to_string(),"{:m true, :file \"positions.clj\", :line 2, :column 7}");
+	let c = match &b {
+	    Value::PersistentList(PersistentList::Cons(_,rest,_,_)) => rest.iter().next().unwrap(),
+	    _ => panic!("Expected a list, got: {}",b)
+	};
+	assert_eq!(c.meta().unwrap().to_string(),"{:file \"positions.clj\", :line 3, :column 4}");
+	// Read without a source,  we can't say where we're from 
+	assert_eq!(try_read(b"(a)").unwrap().1.meta(),None);
+	let err = try_read_source(&source,rest_input).unwrap_err();
+	assert_eq!(err,ReaderError::UnexpectedEof { position: source.position_at(source.text.len()) });
+	assert_eq!(err.position().to_string(),"positions.clj:4:3");
+	// Nothing left to read is no error here 
+	let blank = Source::new("blank.clj"," ;; (a)\n #_ b #?(:clj c)");
+	assert_eq!(try_read_source(&blank,blank.text.as_bytes()).unwrap().1,None);
+    }
+    #[test]
+    fn test_lisp_reader()
+    {
+	let input = "(def a\n1) (+ a\n 2)\n;; done\n(1 2] :skipped\n\"multi\nline\" (3";
+	let forms = LispReader::new("stream.clj",input.as_bytes()).map(|form| match form {
+	    Ok(form) => form.to_string_explicit(),
+	    Err(err) => format!("{} at {}",err,err.position())
+	}).collect::<Vec<String>>();
+	assert_eq!(forms,vec!["(def a 1)","(+ a 2)",
+			      "Unmatched delimiter: ] at stream.clj:5:5",
+			      "\"multi\\nline\"",
+			      "EOF while reading at stream.clj:7:9"]);
+	let mut reader = LispReader::new("stream.clj",&b"(+ a\n 2)"[..]);
+	let form = reader.next().unwrap().unwrap();
+	assert_eq!(form.meta().unwrap().to_string(),"{:file \"stream.clj\", :line 1, :column 1}");
+	assert!(reader.next().is_none());
+	assert!(LispReader::new("empty.clj",&b" ;; nothing\n"[..]).next().is_none());
+	assert!(LispReader::new("empty.clj",&b"#_ (a\n b) #?(:clj 1)"[..]).next().is_none());
+	// However forms fall across lines 
+	let forms = LispReader::new("stream.clj",&b"1 2\n\\( \"(\" ;; (\n'\n[a\n#_ b]"[..]).map(|form| form.unwrap().to_string_explicit()).collect::<Vec<String>>();
+	assert_eq!(forms,vec!["1","2","\\(","\"(\"","(quote [a])"]);
+	// We only hold on to the lines of the form we're reading 
+	let mut reader = LispReader::new("stream.clj",&b"(a)\n(b)\n  (c\n d)"[..]);
+	reader.next();
+	reader.next();
+	let form = reader.next().unwrap().unwrap();
+	assert_eq!(form.meta().unwrap().to_string(),"{:file \"stream.clj\", :line 3, :column 3}");
+	assert_eq!(reader.text,"  (c\n d)");
+    }
+    #[test]
+    fn test_read_reader_conditionals()
+    {
+	let read = |input: &str| try_read(input.as_bytes()).unwrap().1.to_string_explicit();
+	assert_eq!(read("#?(:clj 1 :rs 2)"),"2");
+	assert_eq!(read("#?(:cljs 1 :default 3)"),"3");
+	assert_eq!(read("#?(:clj 1) 4"),"4");
+	assert_eq!(read("[1 #?(:clj 2) #?@(:rs [3 4] :clj [5]) #?@(:clj [6])]"),"[1 3 4]");
+	assert_eq!(try_read(b"{:a #?@(:rs [1 :b] :default [2 :c]) 2}").unwrap().1,try_read(b"{:a 1 :b 2}").unwrap().1);
+	assert!(matches!(try_read(b"#?@(:rs [1])"),Err(ReaderError::InvalidToken { .. })));
+	assert!(matches!(try_read(b"[#?@(:rs 1)]"),Err(ReaderError::InvalidToken { .. })));
+	assert!(matches!(try_read(b"#?(:rs)"),Err(ReaderError::InvalidToken { .. })));
+	assert!(matches!(try_read(b"#?(:rs 1"),Err(ReaderError::UnexpectedEof { .. })));
+	// Whoever's embedding us can say which features we have 
+	let features = reader_features();
+	set_reader_features(vec![Keyword::intern("clj")]);
+	assert_eq!(read("#?(:rs 1 :clj 2)"),"2");
+	set_reader_features(features);
+	assert_eq!(read("#?(:rs 1 :clj 2)"),"1");
+	assert_eq!(read("(1 #?(:clj 2))"),"(1)");
+	assert_eq!(read("{:a 1 #?(:clj :b) #?(:clj 2)}"),"{:a 1}");
+	assert_eq!(read("[#_ #?(:clj 1) 2 3]"),"[3]");
+	let read_source = |input: &str| {
+	    let source = Source::new("conditionals.clj",input);
+	    try_read_source(&source,source.text.as_bytes()).unwrap().1.map(|form| form.to_string())
+	};
+	assert_eq!(read_source(" #?(:clj 1) ;; done"),None);
+	assert_eq!(read_source(" #?(:clj 1 :rs 2)"),Some(String::from("2")));
+	// Each conditional is read once,  however deeply it's nested;  read twice at each
+	// level,  this would take 2^64 reads 
+	let nested = format!("{}:a{}","#?(:clj 0 :rs ".repeat(64),")".repeat(64));
+	assert_eq!(read(&nested),":a");
+    }
+    #[test]
+    fn test_read_tagged_literals()
+    {
+	let read = |input: &str| try_read(input.as_bytes()).unwrap().1;
+	let inst = read("#inst \"2020-06-01T12:00:00+02:00\"");
+	assert_eq!(inst,Value::Inst(Inst::parse("2020-06-01T10:00:00Z").unwrap()));
+	assert_eq!(inst.to_string_explicit(),"#inst \"2020-06-01T10:00:00.000-00:00\"");
+	// What we print reads back as what we printed it from 
+	assert_eq!(read(&inst.to_string_explicit()),inst);
+	let uuid = read("[#uuid \"F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6\"]");
+	assert_eq!(uuid.to_string_explicit(),"[#uuid \"f81d4fae-7dec-11d0-a765-00a0c91e6bf6\"]");
+	assert_eq!(read(&uuid.to_string_explicit()),uuid);
+	// Whoever's embedding us can add their own 
+	data_readers::register_data_reader(Symbol::intern("test/twice"),data_readers::DataReader::Native(|form| match form {
+	    Value::I64(i) => Value::I64(i * 2),
+	    _ => Value::Nil
+	}));
+	assert_eq!(read("(#test/twice 21)").to_string(),"(42)");
+	// Tags the branches we don't pick have no need of a reader 
+	assert_eq!(read("#?(:clj #java.io/File \"a\" :rs #test/twice 2)"),Value::I64(4));
+	let describe = |input: &str| {
+	    let err = try_read(input.as_bytes()).unwrap_err();
+	    format!("{} at {}",err,err.position())
+	};
+	assert_eq!(describe("[1 #unknown/tag 2]"),"No reader function for tag unknown/tag at NO_SOURCE_FILE:1:4");
+	assert_eq!(describe("(f #inst \"2020-13-01\")"),"Invalid tagged literal: #inst \"2020-13-01\" at NO_SOURCE_FILE:1:4");
+	assert_eq!(describe("#uuid 1"),"Invalid tagged literal: #uuid 1 at NO_SOURCE_FILE:1:1");
+	assert!(try_read(b"#inst").unwrap_err().is_eof());
+    }
+    thread_local! {
+	static COUNTED_READS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+    #[test]
+    fn test_data_readers_run_once_per_form()
+    {
+	data_readers::register_data_reader(Symbol::intern("test/counted"),data_readers::DataReader::Native(|form| {
+	    COUNTED_READS.with(|reads| reads.set(reads.get() + 1));
+	    form.clone()
+	}));
+	let reads = |input: &str| {
+	    COUNTED_READS.with(|reads| reads.set(0));
+	    let forms = LispReader::new("counted.clj",input.as_bytes()).map(|form| form.map_or_else(|err| err.to_string(),|form| form.to_string_explicit())).collect::<Vec<String>>();
+	    (forms,COUNTED_READS.with(|reads| reads.get()))
+	};
+	// However many lines we try reading our form at before all of it is there 
+	assert_eq!(reads("(f #test/counted 1
+   2
+   3)"),(vec![String::from("(f 1 2 3)")],1));
+	assert_eq!(reads("#?(:clj 0 :rs #?(:clj 0 :rs #?(:clj 0 :rs
+ #?(:clj 0 :rs [#test/counted 1
+]))))"),(vec![String::from("[1]")],1));
+	assert_eq!(reads("{:a #test/counted 1}
+[#test/counted 2
+ #test/counted 3]"),(vec![String::from("{:a 1}"),String::from("[2 3]")],3));
+	// .. discarded ones included,  however many parsers look past them for what comes next 
+	assert_eq!(reads("[#_ #test/counted 1 2 #_ #test/counted 3]\n(a #_ #test/counted 4)\n{#_ #test/counted 5 :a 1}"),
+		   (vec![String::from("[2]"),String::from("(a)"),String::from("{:a 1}")],4));
+	// .. once each for the literals read on the way to the form failing to read 
+	assert_eq!(reads("(#test/counted 1]").1,1);
+	assert_eq!(reads("#_ #test/counted 1\n#?(:clj #test/counted 2)\n'\n#test/counted\n(3\n 4)"),(vec![String::from("(quote (3 4))")],2));
+    }
+    #[test]
+    fn test_reader_state_is_scoped()
+    {
+	// However a read that set it ends,  our state is put back 
+	assert!(try_read(b"#?(:clj [#inst \"yesterday\" }").is_err());
+	assert!(matches!(try_read(b"#inst \"yesterday\""),Err(ReaderError::InvalidTaggedLiteral { .. })));
+	assert!(try_read(b"#(f #(g))").is_err());
+	assert!(try_read(b"#(f % #?(:clj 1 :rs]))").is_err());
+	assert!(try_read(b"#(inc %)").is_ok());
+	// A data reader reading a form of its own,  partway through a #(..),  reads it afresh 
+	data_readers::register_data_reader(Symbol::intern("test/reads-fn"),data_readers::DataReader::Native(|_| {
+	    try_read(b"#(inc %)").map_or(Value::Nil,|(_,form)| form)
+	}));
+	let read = try_read(b"#(map #test/reads-fn 0 %)").unwrap().1.to_string();
+	assert!(read.contains("(map (fn ["),"{}",read);
+    }
+    #[test]
+    fn test_reader_errors()
+    {
+	let read_err = |input: &str| try_read(input.as_bytes()).unwrap_err();
+	let describe = |input: &str| {
+	    let err = read_err(input);
+	    format!("{} at {}",err,err.position())
+	};
+	assert_eq!(describe("(1 2]"),"Unmatched delimiter: ] at NO_SOURCE_FILE:1:5");
+	assert_eq!(describe("[1\n (2 3}]"),"Unmatched delimiter: } at NO_SOURCE_FILE:2:6");
+	assert_eq!(describe(" )"),"Unmatched delimiter: ) at NO_SOURCE_FILE:1:2");
+	assert_eq!(describe("(str \"abc"),"EOF while reading string at NO_SOURCE_FILE:1:6");
+	assert_eq!(describe("[1 12cat]"),"Invalid number: 12cat at NO_SOURCE_FILE:1:4");
+	assert_eq!(describe("##Foo"),"Invalid number: ##Foo at NO_SOURCE_FILE:1:1");
+	assert_eq!(describe("(\\ab)"),"Invalid token: \\ab at NO_SOURCE_FILE:1:2");
+	assert_eq!(describe("\"a\\q\""),"Invalid token: \\q at NO_SOURCE_FILE:1:3");
+	assert_eq!(describe("(1\n  (2"),"EOF while reading at NO_SOURCE_FILE:2:5");
+	assert!(read_err("(1 2").is_eof());
+	assert!(!read_err("(1 2]").is_eof());
+	let condition = read_err("1/0").to_condition();
+	assert_eq!(condition.message,"Reader Error: Invalid number: 1/0");
+	assert_eq!(condition.condition_type,Keyword::intern("reader-error").to_rc_value());
+    }
+
+    #[test]
+    fn test_read_numbers()
+    {
+	assert_eq!(try_read(b"12 ").unwrap().1,Value::I64(12));
+	assert_eq!(try_read(b"-12 ").unwrap().1,Value::I64(-12));
+	assert_eq!(try_read(b"+7").unwrap().1,Value::I64(7));
+	assert_eq!(try_read(b"1.5").unwrap().1,Value::F64(1.5));
+	assert_eq!(try_read(b"-1.5e3").unwrap().1,Value::F64(-1500.0));
+	assert_eq!(try_read(b"1e10").unwrap().1,Value::F64(1e10));
+	assert_eq!(try_read(b"##Inf").unwrap().1,Value::F64(f64::INFINITY));
+	assert_eq!(try_read(b"##-Inf").unwrap().1,Value::F64(f64::NEG_INFINITY));
+	assert!(matches!(try_read(b"##NaN").unwrap().1,Value::F64(nan) if nan.is_nan()));
+	assert_eq!(try_read(b"0xFF").unwrap().1,Value::I64(255));
+	assert_eq!(try_read(b"-0x10").unwrap().1,Value::I64(-16));
+	assert_eq!(try_read(b"-0x8000000000000000").unwrap().1,Value::I64(i64::MIN));
+	assert_eq!(try_read(b"0x8000000000000000").unwrap().1,Value::BigInt("9223372036854775808".parse().unwrap()));
+	assert_eq!(try_read(b"2r1010").unwrap().1,Value::I64(10));
+	assert_eq!(try_read(b"36rZZ").unwrap().1,Value::I64(1295));
+	// Still symbols 
+	assert_eq!(try_read(b"-").unwrap().1,Value::Symbol(Symbol::intern("-")));
+	assert_eq!(try_read(b"-foo").unwrap().1,Value::Symbol(Symbol::intern("-foo")));
+	assert_eq!(try_read(b"12N").unwrap().1,Value::BigInt(12.into()));
+	assert_eq!(try_read(b"99999999999999999999").unwrap().1,Value::BigInt("99999999999999999999".parse().unwrap()));
+	assert_eq!(try_read(b"-1/3").unwrap().1,Value::Ratio(BigRational::new((-1).into(),3.into())));
+	assert_eq!(try_read(b"4/2").unwrap().1,Value::I64(2));
+	assert_eq!(try_read(b"inc'").unwrap().1,Value::Symbol(Symbol::intern("inc'")));
+	assert!(try_read(b"12cat").is_err());
+	assert!(try_read(b"1/0").is_err());
+	assert!(try_read(b"1.5N").is_err());
+	assert!(try_read(b"1.2.3").is_err());
+    }
+}
@@ -0,0 +1,184 @@
+//! What went wrong reading,  and where
+//!
+//! Each of our parsers says why it gave up right where it gives up,  with a ParseError;  what
+//! about its input was wrong in terms of Clojure (an unmatched ],  a 12cat),  and where in
+//! that input.  reader::try_read turns that into a ReaderError,  which says what line and
+//! column of our source it was on
+use crate::value::ToValue;
+use crate::condition::Condition;
+use crate::source::{Source,SourcePosition};
+use crate::keyword::Keyword;
+use crate::maps::MapEntry;
+use crate::persistent_list_map::{ToPersistentListMap,ToPersistentListMapIter};
+
+use nom::error::ErrorKind;
+use std::rc::Rc;
+use std::fmt;
+
+#[derive(Debug,Clone,PartialEq)]
+pub enum ReaderError {
+    /// A ), ] or } with nothing open for it to close,  or closing the wrong thing;  (1 2]
+    UnbalancedDelimiter { delimiter: char, position: SourcePosition },
+    /// Our input ended partway through a string;  position is where the string started
+    UnterminatedString { position: SourcePosition },
+    /// 12cat,  0xZZ,  1/0,  ##Foo
+    InvalidNumber { token: String, position: SourcePosition },
+    /// A map or set literal naming the same key twice;  #{1 2 1},  {:a 1 :a 2}
+    DuplicateKey { key: String, position: SourcePosition },
+    /// A #(..) inside another;  #(map #(inc %) %)
+    NestedFnLiteral { position: SourcePosition },
+    /// Anything else we couldn't read;  \ab,  "\q",  ::ns/a,  ^1 x
+    InvalidToken { token: String, position: SourcePosition },
+    /// A tagged literal whose tag has no data reader;  #unknown/tag 1
+    UnknownTag { tag: String, position: SourcePosition },
+    /// A tagged literal whose data reader couldn't make anything of its form;  #inst "yesterday".
+    /// cause is the Condition the data reader gave 
+    InvalidTaggedLiteral { literal: String, cause: Option<Rc<Condition>>, position: SourcePosition },
+    /// Our input ended partway through a form
+    UnexpectedEof { position: SourcePosition }
+}
+use ReaderError::*;
+
+/// Why one of our parsers gave up,  and where in its input 
+#[derive(Debug,Clone)]
+pub struct ParseError<'a> {
+    pub input: &'a [u8],
+    pub kind: ParseErrorKind
+}
+/// A ReaderError,  before we know what line and column it's at
+#[derive(Debug,Clone)]
+pub enum ParseErrorKind {
+    UnbalancedDelimiter(char),
+    UnterminatedString,
+    InvalidNumber(String),
+    InvalidToken(String),
+    DuplicateKey(String),
+    NestedFnLiteral,
+    UnknownTag(String),
+    /// The literal,  and the Condition its data reader gave 
+    InvalidTaggedLiteral(String,Option<Rc<Condition>>),
+    UnexpectedEof,
+    /// From one of nom's own parsers;  mostly one that didn't match,  so whoever tried it
+    /// moves on to try another 
+    Nom
+}
+impl<'a> ParseError<'a> {
+    /// Gives up for good on input;  nothing else should try reading it as something else 
+    pub fn failure(input: &'a [u8],kind: ParseErrorKind) -> nom::Err<ParseError<'a>> {
+	nom::Err::Failure(ParseError { input, kind })
+    }
+    /// The token input starts with isn't anything we can read 
+    pub fn invalid_token(input: &'a [u8]) -> nom::Err<ParseError<'a>> {
+	ParseError::failure(input,ParseErrorKind::InvalidToken(token(input)))
+    }
+    /// The token input starts with looks like a number,  but isn't one 
+    pub fn invalid_number(input: &'a [u8]) -> nom::Err<ParseError<'a>> {
+	ParseError::failure(input,ParseErrorKind::InvalidNumber(token(input)))
+    }
+}
+impl<'a> nom::error::ParseError<&'a [u8]> for ParseError<'a> {
+    fn from_error_kind(input: &'a [u8],_kind: ErrorKind) -> Self {
+	ParseError { input, kind: ParseErrorKind::Nom }
+    }
+    fn append(_input: &'a [u8],_kind: ErrorKind,other: Self) -> Self {
+	other
+    }
+}
+// What the parsers nom's macros build give up with 
+impl<'a> From<(&'a [u8],ErrorKind)> for ParseError<'a> {
+    fn from((input,_kind): (&'a [u8],ErrorKind)) -> Self {
+	ParseError { input, kind: ParseErrorKind::Nom }
+    }
+}
+
+impl ReaderError {
+    /// The ReaderError for a parser giving up on input,  which is a slice of source's text
+    pub fn from_parse_error(source: &Source,input: &[u8],err: nom::Err<ParseError>) -> ReaderError {
+	let position_of = |input: &[u8]| source.position_of(input).unwrap_or_else(|| source.position_at(0));
+	let err = match err {
+	    nom::Err::Incomplete(_) => return UnexpectedEof { position: position_of(&input[input.len()..]) },
+	    nom::Err::Error(err) | nom::Err::Failure(err) => err
+	};
+	let position = position_of(err.input);
+	match err.kind {
+	    ParseErrorKind::UnbalancedDelimiter(delimiter) => UnbalancedDelimiter { delimiter, position },
+	    ParseErrorKind::UnterminatedString => UnterminatedString { position },
+	    ParseErrorKind::InvalidNumber(token) => InvalidNumber { token, position },
+	    ParseErrorKind::InvalidToken(token) => InvalidToken { token, position },
+	    ParseErrorKind::DuplicateKey(key) => DuplicateKey { key, position },
+	    ParseErrorKind::NestedFnLiteral => NestedFnLiteral { position },
+	    ParseErrorKind::UnknownTag(tag) => UnknownTag { tag, position },
+	    ParseErrorKind::InvalidTaggedLiteral(literal,cause) => InvalidTaggedLiteral { literal, cause, position },
+	    ParseErrorKind::UnexpectedEof => UnexpectedEof { position },
+	    // One of nom's parsers gave up for good,  rather than just not matching;  all we
+	    // know is it couldn't read what's there 
+	    ParseErrorKind::Nom => InvalidToken { token: token(err.input), position }
+	}
+    }
+    pub fn position(&self) -> &SourcePosition {
+	match self {
+	    UnbalancedDelimiter { position, .. } | UnterminatedString { position }
+	    | InvalidNumber { position, .. } | InvalidToken { position, .. }
+	    | DuplicateKey { position, .. } | NestedFnLiteral { position }
+	    | UnknownTag { position, .. } | InvalidTaggedLiteral { position, .. }
+	    | UnexpectedEof { position } => position
+	}
+    }
+    /// Whether we just didn't have enough input yet;  at a REPL,  where the rest of a form
+    /// may well be on the next line,  this is hardly an error at all
+    pub fn is_eof(&self) -> bool {
+	matches!(self,UnexpectedEof { .. } | UnterminatedString { .. })
+    }
+    /// :unbalanced-delimiter,  :unterminated-string ..
+    pub fn kind(&self) -> Keyword {
+	Keyword::intern(match self {
+	    UnbalancedDelimiter { .. } => "unbalanced-delimiter",
+	    UnterminatedString { .. } => "unterminated-string",
+	    InvalidNumber { .. } => "invalid-number",
+	    InvalidToken { .. } => "invalid-token",
+	    DuplicateKey { .. } => "duplicate-key",
+	    NestedFnLiteral { .. } => "nested-fn-literal",
+	    UnknownTag { .. } => "unknown-tag",
+	    InvalidTaggedLiteral { .. } => "invalid-tagged-literal",
+	    UnexpectedEof { .. } => "unexpected-eof"
+	})
+    }
+    /// A condition of type :reader-error,  whose data is {:kind :invalid-number :file .. :line .. :column ..}
+    pub fn to_condition(&self) -> Condition {
+	let position = self.position();
+	let mut data = vec![MapEntry { key: Keyword::intern("kind").to_rc_value(), val: self.kind().to_rc_value() }];
+	data.extend(position.to_meta().iter());
+	Condition {
+	    condition_type: Keyword::intern("reader-error").to_rc_value(),
+	    data: data.into_list_map().to_rc_value(),
+	    position: Some(position.clone()),
+	    cause: match self {
+		InvalidTaggedLiteral { cause, .. } => cause.clone(),
+		_ => None
+	    },
+	    ..Condition::new(format!("Reader Error: {}",self))
+	}
+    }
+}
+/// The rest of the token input starts with;  up to the next whitespace or delimiter
+fn token(input: &[u8]) -> String {
+    let token_len = input.iter().skip(1)
+	.take_while(|byte| !byte.is_ascii_whitespace() && !b"()[]{}\",;".contains(byte))
+	.count() + 1;
+    String::from_utf8_lossy(&input[..token_len.min(input.len())]).into_owned()
+}
+impl fmt::Display for ReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	match self {
+	    UnbalancedDelimiter { delimiter, .. } => write!(f,"Unmatched delimiter: {}",delimiter),
+	    UnterminatedString { .. } => write!(f,"EOF while reading string"),
+	    InvalidNumber { token, .. } => write!(f,"Invalid number: {}",token),
+	    InvalidToken { token, .. } => write!(f,"Invalid token: {}",token),
+	    DuplicateKey { key, .. } => write!(f,"Duplicate key: {}",key),
+	    NestedFnLiteral { .. } => write!(f,"Nested #()s are not allowed"),
+	    UnknownTag { tag, .. } => write!(f,"No reader function for tag {}",tag),
+	    InvalidTaggedLiteral { literal, .. } => write!(f,"Invalid tagged literal: {}",literal),
+	    UnexpectedEof { .. } => write!(f,"EOF while reading")
+	}
+    }
+}
@@ -1,51 +1,50 @@
 use std::fs::File;
-use std::io::{self,Read};
-use std::str::FromStr;
-use std::io::BufRead;
-use std::io::BufReader;
+use std::io;
+use std::io::{BufReader,Stdin};
+use std::cell::RefCell;
 
 use crate::reader;
+use crate::reader::LispReader;
+use crate::reader_error::ReaderError;
 use crate::environment::Environment;
 use crate::value::Value;
 use crate::value::Evaluable;
+use crate::condition::{Condition,DebuggerHook,Restart,RestartInvocation};
 use std::rc::Rc;
-
-use nom::Err::Incomplete;
-use nom::error::convert_error;
-use nom::Needed::Size;
+use std::io::Write;
 
 //
 // Will possibly just add this to our environment, or turn this into a parallel of clojure.lang.RT 
 //
 
-pub fn try_eval_file(environment: &Rc<Environment>,filepath: &str) -> Result<(),io::Error>{
-    let core = File::open(filepath)?;
-
-    
-    let reader = BufReader::new(core);
+thread_local! {
+    // Our stdin,  read by the REPL and by (read) alike,  so neither loses forms the other has
+    // read ahead.  We leave buffering it to stdin itself,  so the debugger's prompts,  which
+    // read from stdin directly,  never miss a line we've taken 
+    static STDIN_READER: RefCell<LispReader<BufReader<Stdin>>> =
+	RefCell::new(LispReader::new("NO_SOURCE_PATH",BufReader::with_capacity(1,io::stdin())));
+}
+/// The next form from stdin,  or None once there's nothing left to read 
+pub fn read_stdin() -> Option<Result<Value,ReaderError>> {
+    STDIN_READER.with(|stdin_reader| stdin_reader.borrow_mut().next())
+}
 
-    let mut remaining_input_buffer = String::from("");
-    for line in reader.lines() {
-	let line = line?;
-	remaining_input_buffer.push_str(&line);
-	let mut remaining_input_bytes = remaining_input_buffer.as_bytes();
-	loop {
-	    let next_read_parse = reader::try_read(remaining_input_bytes);
-	    match next_read_parse {
-		Ok((_remaining_input,value)) => {
-		    //print!("{} ",value.eval(Rc::clone(&environment)).to_string_explicit());
-		    value.eval(Rc::clone(&environment));
-		    remaining_input_bytes = _remaining_input;
-		},
-		Err(Incomplete(Size(1))) => {
-		    remaining_input_buffer = String::from_utf8(remaining_input_bytes.to_vec()).unwrap();
-		    break;
-		},
-		err => {
-		    println!("Error evaluating file {}; {}",filepath,Value::Condition(format!("Reader Error: {:?}",err)));
-		    remaining_input_buffer = String::from("");
-		    break;
+/// Reads and evaluates each form in the file at filepath in turn;  a .clj file,  or a .cljc
+/// one shared with other Clojures,  whose reader conditionals pick the branch for our
+/// features (see reader::set_reader_features).  We don't hold a .clj file's reader
+/// conditionals against it (see reader::reader_conditional)
+pub fn try_eval_file(environment: &Rc<Environment>,filepath: &str) -> Result<(),io::Error>{
+    let file = File::open(filepath)?;
+    for form in LispReader::new(filepath,BufReader::new(file)) {
+	match form {
+	    Ok(form) => {
+		if let Value::Condition(condition) = form.eval(Rc::clone(environment)) {
+		    println!("Error evaluating file {}; {}",filepath,condition.report());
 		}
+	    },
+	    Err(err) => {
+		println!("Error reading file {}; {}",filepath,err.to_condition().report());
+		break;
 	    }
 	}
     }
@@ -53,3 +52,55 @@ pub fn try_eval_file(environment: &Rc<Environment>,filepath: &str) -> Result<(),
     Ok(())
     
 }
+
+/// Reads a line from stdin,  or None if there's nothing left to read
+fn prompt_line(prompt: &str) -> Option<String> {
+    print!("{}",prompt);
+    io::stdout().flush().ok()?;
+    let mut line = String::new();
+    match io::stdin().read_line(&mut line) {
+	Ok(0) | Err(_) => None,
+	Ok(_) => Some(line)
+    }
+}
+/// Lets whoever's at the REPL choose a restart for an unhandled condition,  reading and
+/// evaluating (in environment) a value for each of its parameters 
+pub fn interactive_debugger_hook(environment: Rc<Environment>) -> DebuggerHook {
+    Rc::new(move |condition: &Condition,restarts: &[Restart]| -> Option<RestartInvocation> {
+	// Nothing to choose from;  abort on up to the top level,  where we'll be reported 
+	if restarts.is_empty() {
+	    return None;
+	}
+	println!("Unhandled {}",condition.report());
+	println!("Restarts:");
+	println!("  0: [abort] Return to the top level");
+	// Innermost restart-case's restarts first,  each in the order they were written 
+	let mut restarts = restarts.iter().collect::<Vec<&Restart>>();
+	restarts.sort_by_key(|restart| std::cmp::Reverse(restart.restart_case_id));
+	for (ind,restart) in restarts.iter().enumerate() {
+	    let params = restart.params.iter().map(|param| param.to_string()).collect::<Vec<String>>().join(" ");
+	    println!("  {}: [{}] [{}]",ind + 1,restart.name,params);
+	}
+	let restart = loop {
+	    let choice = prompt_line("Choose a restart: ")?;
+	    match choice.trim().parse::<usize>() {
+		Ok(0) => return None,
+		Ok(ind) if ind <= restarts.len() => break restarts[ind - 1],
+		_ => println!("Please enter a number from 0 to {}",restarts.len())
+	    }
+	};
+	let mut args = vec![];
+	for param in restart.params.iter() {
+	    let arg = loop {
+		let input = prompt_line(&format!("{}: ",param))?;
+		// Trailing whitespace lets our reader know a number or symbol has ended
+		match reader::try_read(input.as_bytes()) {
+		    Ok((_,form)) => break form.eval_to_rc(Rc::clone(&environment)),
+		    Err(err) => println!("{}",err.to_condition().report())
+		}
+	    };
+	    args.push(arg);
+	}
+	Some(restart.invocation(args))
+    })
+}
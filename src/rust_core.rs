@@ -1,14 +1,23 @@
 use crate::value::Value;
 use std::rc::Rc;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash,Hasher};
 
 use crate::ifn::IFn;
 use crate::value::{ToValue,Evaluable};
 use crate::environment::Environment;
-use crate::persistent_list::{ToPersistentListIter,PersistentList,PersistentList::{Cons,Empty},ToPersistentList};
-use crate::persistent_vector::{ToPersistentVectorIter,PersistentVector,ToPersistentVector};
-use crate::symbol::Symbol;
-use crate::lambda::Fn;
-use std::collections::HashMap;
+use crate::condition;
+use crate::condition::Condition;
+use crate::numbers::{self,Number};
+use crate::persistent_list::{self,ToPersistentList,ToPersistentListIter,PersistentList,PersistentList::{Cons,Empty}};
+use crate::persistent_vector::{ToPersistentVectorIter,PersistentVector};
+use crate::persistent_list_map::{PersistentListMap,IPersistentListMap,ToPersistentListMapIter};
+use crate::persistent_hash_set::PersistentHashSet;
+use crate::maps::MapEntry;
+use crate::reader;
+use crate::repl;
 
 //
 // This module will hold the core functions and macros that Clojure will
@@ -39,202 +48,1423 @@ impl IFn for StrFn {
 }
 
 
+/// Our args as Numbers,  or a type mismatch Condition for the first that isn't one
+fn args_to_numbers(args: &[&Value]) -> Result<Vec<Number>,Value> {
+    args.iter().map(|arg| {
+	Number::from_value(arg).ok_or_else(|| {
+	    condition::raise(format!("Type mismatch; Expecting: (i32 | i64 | BigInt | Ratio | f64), Found: {}",arg.type_tag()))
+	})
+    }).collect()
+}
+/// Folds op over numbers,  starting from init 
+fn fold_numbers(init: Number,numbers: &[Number],op: fn(Number,Number) -> Result<Number,Value>) -> Value {
+    numbers.iter().try_fold(init,|a,b| op(a,b.clone())).map_or_else(|condition| condition,Number::into_value)
+}
+/// Whether each number compared to the next passes test;  (< 1 2 3), (>= 3 3 1) ..
+fn compare_chain(args: Vec<&Value>,test: fn(Ordering) -> bool) -> Value {
+    if args.is_empty() {
+	return condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)"));
+    }
+    match args_to_numbers(&args) {
+	Ok(numbers) => Value::Boolean(numbers.windows(2).all(|pair| pair[0].compare(&pair[1]).is_some_and(test))),
+	Err(condition) => condition
+    }
+}
+/// Applies op to our one numeric argument
+fn unary_number_fn(args: Vec<&Value>,op: fn(Number) -> Result<Value,Value>) -> Value {
+    if args.len() != 1 {
+	return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+    }
+    match args_to_numbers(&args).and_then(|mut numbers| op(numbers.remove(0))) {
+	Ok(value) => value,
+	Err(condition) => condition
+    }
+}
+/// Applies op to our two numeric arguments
+fn binary_number_fn(args: Vec<&Value>,op: fn(Number,Number) -> Result<Number,Value>) -> Value {
+    if args.len() != 2 {
+	return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2)",args.len()));
+    }
+    match args_to_numbers(&args).and_then(|mut numbers| op(numbers.remove(0),numbers.remove(0))) {
+	Ok(number) => number.into_value(),
+	Err(condition) => condition
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct AddFn {
+}
+impl ToValue for AddFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for AddFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	match args_to_numbers(&args) {
+	    Ok(numbers) => fold_numbers(Number::Integer(0),&numbers,Number::add),
+	    Err(condition) => condition
+	}
+    }
+}
+
+/// (- x) => negative x,  (- x y z) => x minus y minus z 
+#[derive(Debug,Clone)]
+pub struct SubtractFn {
+}
+impl ToValue for SubtractFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for SubtractFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	match args_to_numbers(&args).as_deref() {
+	    Ok([]) => condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)")),
+	    Ok([x]) => fold_numbers(Number::Integer(0),std::slice::from_ref(x),Number::subtract),
+	    Ok([x,rest @ ..]) => fold_numbers(x.clone(),rest,Number::subtract),
+	    Err(condition) => condition.clone()
+	}
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct MultiplyFn {
+}
+impl ToValue for MultiplyFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for MultiplyFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	match args_to_numbers(&args) {
+	    Ok(numbers) => fold_numbers(Number::Integer(1),&numbers,Number::multiply),
+	    Err(condition) => condition
+	}
+    }
+}
+
+/// (/ x) => 1 divided by x,  (/ x y z) => x divided by y divided by z 
+#[derive(Debug,Clone)]
+pub struct DivideFn {
+}
+impl ToValue for DivideFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for DivideFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	match args_to_numbers(&args).as_deref() {
+	    Ok([]) => condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)")),
+	    Ok([x]) => fold_numbers(Number::Integer(1),std::slice::from_ref(x),Number::divide),
+	    Ok([x,rest @ ..]) => fold_numbers(x.clone(),rest,Number::divide),
+	    Err(condition) => condition.clone()
+	}
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct QuotFn {
+}
+impl ToValue for QuotFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for QuotFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	binary_number_fn(args,Number::quot)
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct RemFn {
+}
+impl ToValue for RemFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for RemFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	binary_number_fn(args,Number::rem)
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct ModFn {
+}
+impl ToValue for ModFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ModFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	binary_number_fn(args,Number::modulo)
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct IncFn {
+}
+impl ToValue for IncFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for IncFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	unary_number_fn(args,|x| x.add(Number::Integer(1)).map(Number::into_value))
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct DecFn {
+}
+impl ToValue for DecFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for DecFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	unary_number_fn(args,|x| x.subtract(Number::Integer(1)).map(Number::into_value))
+    }
+}
+
+// The auto-promoting versions of our arithmetic;  where + would overflow an i64 with an
+// arithmetic-error,  +' carries on as a BigInt 
+
+#[derive(Debug,Clone)]
+pub struct AddPromotingFn {
+}
+impl ToValue for AddPromotingFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for AddPromotingFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	match args_to_numbers(&args) {
+	    Ok(numbers) => fold_numbers(Number::Integer(0),&numbers,Number::add_promoting),
+	    Err(condition) => condition
+	}
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct SubtractPromotingFn {
+}
+impl ToValue for SubtractPromotingFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for SubtractPromotingFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	match args_to_numbers(&args).as_deref() {
+	    Ok([]) => condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)")),
+	    Ok([x]) => fold_numbers(Number::Integer(0),std::slice::from_ref(x),Number::subtract_promoting),
+	    Ok([x,rest @ ..]) => fold_numbers(x.clone(),rest,Number::subtract_promoting),
+	    Err(condition) => condition.clone()
+	}
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct MultiplyPromotingFn {
+}
+impl ToValue for MultiplyPromotingFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for MultiplyPromotingFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	match args_to_numbers(&args) {
+	    Ok(numbers) => fold_numbers(Number::Integer(1),&numbers,Number::multiply_promoting),
+	    Err(condition) => condition
+	}
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct IncPromotingFn {
+}
+impl ToValue for IncPromotingFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for IncPromotingFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	unary_number_fn(args,|x| x.add_promoting(Number::Integer(1)).map(Number::into_value))
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct DecPromotingFn {
+}
+impl ToValue for DecPromotingFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for DecPromotingFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	unary_number_fn(args,|x| x.subtract_promoting(Number::Integer(1)).map(Number::into_value))
+    }
+}
+
+/// (max x y z) => whichever of x, y or z is largest,  as is 
+#[derive(Debug,Clone)]
+pub struct MaxFn {
+}
+impl ToValue for MaxFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for MaxFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	extreme_number(args,Ordering::Greater)
+    }
+}
+
+/// (min x y z) => whichever of x, y or z is smallest,  as is 
+#[derive(Debug,Clone)]
+pub struct MinFn {
+}
+impl ToValue for MinFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for MinFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	extreme_number(args,Ordering::Less)
+    }
+}
+/// The arg that compares as `ordering` to all the others;  the largest or the smallest
+fn extreme_number(args: Vec<&Value>,ordering: Ordering) -> Value {
+    let numbers = match args_to_numbers(&args) {
+	Ok(numbers) => numbers,
+	Err(condition) => return condition
+    };
+    let mut extreme_ind = match numbers.first() {
+	Some(_) => 0,
+	None => return condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)"))
+    };
+    for (ind,number) in numbers.iter().enumerate().skip(1) {
+	if number.compare(&numbers[extreme_ind]) == Some(ordering) {
+	    extreme_ind = ind;
+	}
+    }
+    args[extreme_ind].clone()
+}
+
+#[derive(Debug,Clone)]
+pub struct EqualsFn {
+}
+impl ToValue for EqualsFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for EqualsFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.is_empty() {
+	    return condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)"));
+	}
+	Value::Boolean(args.windows(2).all(|pair| numbers::equiv(pair[0],pair[1])))
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct NotEqualsFn {
+}
+impl ToValue for NotEqualsFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for NotEqualsFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	match (EqualsFn{}).invoke(args) {
+	    Value::Boolean(equal) => Value::Boolean(!equal),
+	    condition => condition
+	}
+    }
+}
+
+/// (== 1 1.0) => true;  numeric equality,  across integers and floats alike 
+#[derive(Debug,Clone)]
+pub struct NumEqualsFn {
+}
+impl ToValue for NumEqualsFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for NumEqualsFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	compare_chain(args,|ordering| ordering == Ordering::Equal)
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct LessThanFn {
+}
+impl ToValue for LessThanFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for LessThanFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	compare_chain(args,|ordering| ordering == Ordering::Less)
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct LessThanOrEqualFn {
+}
+impl ToValue for LessThanOrEqualFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for LessThanOrEqualFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	compare_chain(args,|ordering| ordering != Ordering::Greater)
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct GreaterThanFn {
+}
+impl ToValue for GreaterThanFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for GreaterThanFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	compare_chain(args,|ordering| ordering == Ordering::Greater)
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct GreaterThanOrEqualFn {
+}
+impl ToValue for GreaterThanOrEqualFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for GreaterThanOrEqualFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	compare_chain(args,|ordering| ordering != Ordering::Less)
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct IsZeroFn {
+}
+impl ToValue for IsZeroFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for IsZeroFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	unary_number_fn(args,|x| Ok(Value::Boolean(x.is_zero())))
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct IsPosFn {
+}
+impl ToValue for IsPosFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for IsPosFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	unary_number_fn(args,|x| Ok(Value::Boolean(x.compare(&Number::Integer(0)) == Some(Ordering::Greater))))
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct IsNegFn {
+}
+impl ToValue for IsNegFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for IsNegFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	unary_number_fn(args,|x| Ok(Value::Boolean(x.compare(&Number::Integer(0)) == Some(Ordering::Less))))
+    }
+}
+
+
+/// (numerator 2/3) => 2 
+#[derive(Debug,Clone)]
+pub struct NumeratorFn {
+}
+impl ToValue for NumeratorFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for NumeratorFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	ratio_part_fn(args,|ratio| Value::BigInt(ratio.numer().clone()))
+    }
+}
+
+/// (denominator 2/3) => 3 
+#[derive(Debug,Clone)]
+pub struct DenominatorFn {
+}
+impl ToValue for DenominatorFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for DenominatorFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	ratio_part_fn(args,|ratio| Value::BigInt(ratio.denom().clone()))
+    }
+}
+/// Applies part to our one argument,  which must be a ratio 
+fn ratio_part_fn(args: Vec<&Value>,part: fn(&num_rational::BigRational) -> Value) -> Value {
+    if args.len() != 1 {
+	return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+    }
+    match args[0] {
+	Value::Ratio(ratio) => part(ratio),
+	arg => condition::raise(format!("Type mismatch; Expecting: Ratio, Found: {}",arg.type_tag()))
+    }
+}
+
+/// (bigint 3.7) => 3N;  truncating,  as with quot 
+#[derive(Debug,Clone)]
+pub struct BigIntFn {
+}
+impl ToValue for BigIntFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for BigIntFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	unary_number_fn(args,|x| x.truncate_to_bigint().map(Number::into_value))
+    }
+}
+
+/// (rationalize 0.25) => 1/4;  the exact ratio for a float,  anything else as is 
+#[derive(Debug,Clone)]
+pub struct RationalizeFn {
+}
+impl ToValue for RationalizeFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for RationalizeFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	unary_number_fn(args,|x| x.rationalize().map(Number::into_value))
+    }
+}
+
+
+#[derive(Debug,Clone)]
+pub struct EvalFn {
+    enclosing_environment: Rc<Environment>
+}
+impl EvalFn {
+    pub fn new(enclosing_environment: Rc<Environment>) -> EvalFn {
+	EvalFn{enclosing_environment}
+    }
+}
+impl ToValue for EvalFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for EvalFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	// @TODO generalize arity exceptions, and other exceptions 
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments given to function (Given: {}, Expected: {})",args.len(),args.len()));
+	}
+	let arg = args.first().unwrap();
+	arg.eval(Rc::clone(&self.enclosing_environment))
+    }
+}
+
+
+/// (alias 'set 'clojure.set);  from here on,  set/union means clojure.set/union 
+#[derive(Debug,Clone)]
+pub struct AliasFn {
+    enclosing_environment: Rc<Environment>
+}
+impl AliasFn {
+    pub fn new(enclosing_environment: Rc<Environment>) -> AliasFn {
+	AliasFn{enclosing_environment}
+    }
+}
+impl ToValue for AliasFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for AliasFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2)",args.len()));
+	}
+	match (args[0],args[1]) {
+	    (Value::Symbol(alias),Value::Symbol(ns)) => {
+		self.enclosing_environment.add_alias(alias.unqualified(),ns.unqualified());
+		Value::Nil
+	    },
+	    _ => condition::raise(format!("Type mismatch; Expected instances of clojure.lang.Symbol, Recieved types {} and {}",args[0].type_tag(),args[1].type_tag()))
+	}
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct NthFn {
+}
+impl ToValue for NthFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for NthFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	// @TODO generalize arity exceptions, and other exceptions 
+	if args.len() != 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1-2)",args.len()));
+	}
+	// @TODO change iteration to work with Value references, or even change invoke to work on Rc<..>
+	//       as we do everything else; surely we don't want to clone just to read from a collection 
+	if let Some(Number::Integer(ind)) = args.get(1).and_then(|ind| Number::from_value(ind)) {
+	    if ind < 0 {
+		return condition::raise(format!("Index cannot be negative; Index ({})",ind));
+	    }
+	    let ind = ind as usize;
+	    
+	    match args.first().unwrap() {
+		Value::PersistentList(Cons(head,tail,count,_)) => {
+		    let count = *count as usize;
+		    if ind >= count {
+			condition::raise(format!("Index out of bounds: Index ({}), Length: ({})",ind,count))
+		    }
+		    else if ind == 0 {
+			head.to_value()
+		    }
+		    else {
+			tail.iter().nth(ind - 1).unwrap().to_value()
+		    }
+		},
+		Value::PersistentList(Empty(_)) => {
+		    condition::raise(format!("Index out of bounds: Index ({}), Length: ({})",ind,0))
+		},
+		Value::PersistentVector(PersistentVector { vals, .. }) => {
+		    if ind >= vals.len() {
+			condition::raise(format!("Index out of bounds: Index ({}), Length: ({})",ind,vals.len()))
+		    }
+		    else {
+			vals.get(ind).unwrap().to_value()
+		    }
+		},
+		// A string is a seq of its chars 
+		Value::String(string) => match string.chars().nth(ind) {
+		    Some(ch) => Value::Char(ch),
+		    None => condition::raise(format!("Index out of bounds: Index ({}), Length: ({})",ind,string.chars().count()))
+		},
+	    _ => condition::raise(format!("Type mismatch; Expected instance of clojure.lang.ISeq, Recieved type {}",args.first().unwrap().type_tag()))
+	    }
+	}
+	else {
+	    condition::raise(format!("Type mismatch; Expected instance of clojure.lang.Integer,  Recieved type {}",args.get(1).unwrap().type_tag()))
+	}
+    }
+}
+
+
+#[derive(Debug,Clone)]
+pub struct ConcatFn {
+}
+impl ToValue for ConcatFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ConcatFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	let concatted_vec = args.iter().fold(vec![],|mut sum,coll| {
+	    let coll_vec = match coll {
+		Value::PersistentList(plist) =>
+		    Rc::new(plist.clone()).iter().collect::<Vec<Rc<Value>>>(),
+		Value::PersistentVector(pvector) =>
+		    Rc::new(pvector.clone()).iter().collect::<Vec<Rc<Value>>>(),
+		Value::PersistentHashSet(phashset) =>
+		    phashset.iter().collect::<Vec<Rc<Value>>>(),
+		Value::String(string) =>
+		    string.chars().map(|ch| ch.to_rc_value()).collect::<Vec<Rc<Value>>>(),
+		_ => vec![]
+	    };
+	    
+	    sum.extend(coll_vec);
+	    sum	
+	});
+	Value::PersistentList(concatted_vec.into_iter().collect::<PersistentList>())
+    }
+}
+
+/// (first [1 2 3]) => 1,  (first "abc") => \\a,  (first nil) => nil 
+#[derive(Debug,Clone)]
+pub struct FirstFn {
+}
+impl ToValue for FirstFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for FirstFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match args[0] {
+	    Value::PersistentList(Cons(head,..)) => head.to_value(),
+	    Value::PersistentList(Empty(_)) => Value::Nil,
+	    Value::PersistentVector(PersistentVector { vals, .. }) => vals.first().map_or(Value::Nil,|val| val.to_value()),
+	    Value::PersistentHashSet(phashset) => phashset.iter().next().map_or(Value::Nil,|val| val.to_value()),
+	    Value::String(string) => string.chars().next().map_or(Value::Nil,Value::Char),
+	    Value::Nil => Value::Nil,
+	    arg => condition::raise(format!("Don't know how to create ISeq from: {}",arg.type_tag()))
+	}
+    }
+}
+
+/// (rest [1 2 3]) => (2 3),  (rest nil) => () 
+#[derive(Debug,Clone)]
+pub struct RestFn {
+}
+impl ToValue for RestFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for RestFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match args[0] {
+	    Value::PersistentList(Cons(_,tail,..)) => Value::PersistentList((**tail).clone()),
+	    Value::PersistentList(Empty(_)) | Value::Nil => Value::PersistentList(Empty(None)),
+	    Value::PersistentVector(PersistentVector { vals, .. }) => vals.iter().skip(1).cloned().collect::<Vec<Rc<Value>>>().into_list().to_value(),
+	    Value::PersistentHashSet(phashset) => phashset.iter().skip(1).collect::<Vec<Rc<Value>>>().into_list().to_value(),
+	    Value::String(string) => string.chars().skip(1).map(|ch| Rc::new(Value::Char(ch))).collect::<Vec<Rc<Value>>>().into_list().to_value(),
+	    arg => condition::raise(format!("Don't know how to create ISeq from: {}",arg.type_tag()))
+	}
+    }
+}
+
+/// (count [1 2 3]) => 3,  (count "Ωmega") => 5 
+#[derive(Debug,Clone)]
+pub struct CountFn {
+}
+impl ToValue for CountFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for CountFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	let count = match args[0] {
+	    Value::PersistentList(plist) => plist.len() as usize,
+	    Value::PersistentVector(PersistentVector { vals, .. }) => vals.len(),
+	    Value::PersistentListMap(plistmap) => plistmap.iter().count(),
+	    Value::PersistentHashSet(phashset) => phashset.len(),
+	    // Chars,  not bytes 
+	    Value::String(string) => string.chars().count(),
+	    Value::Nil => 0,
+	    arg => return condition::raise(format!("count not supported on this type: {}",arg.type_tag()))
+	};
+	Value::I64(count as i64)
+    }
+}
+
+/// (vec (list 1 2 3)) => [1 2 3] 
+#[derive(Debug,Clone)]
+pub struct VecFn {
+}
+impl ToValue for VecFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for VecFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match args[0] {
+	    Value::PersistentList(plist) => Value::PersistentVector(Rc::new(plist.clone()).iter().collect::<PersistentVector>()),
+	    Value::PersistentVector(pvector) => Value::PersistentVector(pvector.clone()),
+	    Value::PersistentHashSet(phashset) => Value::PersistentVector(phashset.iter().collect::<PersistentVector>()),
+	    Value::String(string) => Value::PersistentVector(string.chars().map(|ch| ch.to_rc_value()).collect::<PersistentVector>()),
+	    Value::Nil => Value::PersistentVector(PersistentVector::from(vec![])),
+	    arg => condition::raise(format!("Don't know how to create ISeq from: {}",arg.type_tag()))
+	}
+    }
+}
+
+/// The elements of a list,  vector or set (or nil,  which has none);  None if coll is
+/// none of these 
+fn coll_vals(coll: &Value) -> Option<Vec<Rc<Value>>> {
+    match coll {
+	Value::PersistentList(plist) => Some(Rc::new(plist.clone()).iter().collect()),
+	Value::PersistentVector(pvector) => Some(pvector.vals.clone()),
+	Value::PersistentHashSet(phashset) => Some(phashset.iter().collect()),
+	Value::Nil => Some(vec![]),
+	_ => None
+    }
+}
+/// arg as a set,  or the condition to return if it isn't one 
+fn set_arg(arg: &Value) -> Result<&PersistentHashSet,Value> {
+    match arg {
+	Value::PersistentHashSet(phashset) => Ok(phashset),
+	_ => Err(condition::raise(format!("Type mismatch; Expected instance of clojure.lang.PersistentHashSet,  Recieved type {}",arg.type_tag())))
+    }
+}
+
+/// (set [1 2 1]) => #{1 2} 
+#[derive(Debug,Clone)]
+pub struct SetFn {
+}
+impl ToValue for SetFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for SetFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match args[0] {
+	    Value::String(string) => Value::PersistentHashSet(string.chars().map(|ch| ch.to_rc_value()).collect::<PersistentHashSet>()),
+	    arg => match coll_vals(arg) {
+		Some(vals) => Value::PersistentHashSet(vals.into_iter().collect::<PersistentHashSet>()),
+		None => condition::raise(format!("Don't know how to create ISeq from: {}",arg.type_tag()))
+	    }
+	}
+    }
+}
+
+/// (hash-set 1 2 1) => #{1 2} 
+#[derive(Debug,Clone)]
+pub struct HashSetFn {
+}
+impl ToValue for HashSetFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for HashSetFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	Value::PersistentHashSet(args.into_iter().map(|arg| arg.to_rc_value()).collect::<PersistentHashSet>())
+    }
+}
+
+/// (conj coll & xs)
+///
+/// coll with xs added wherever coll adds things;  the front of a list,  the end of a
+/// vector.  A map is given [key val] entries (or whole maps) to add 
+///    (conj [1 2] 3 4) => [1 2 3 4],  (conj '(1 2) 3 4) => (4 3 1 2),  (conj nil 1) => (1)
+#[derive(Debug,Clone)]
+pub struct ConjFn {
+}
+impl ToValue for ConjFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ConjFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	let (coll,xs) = match args.split_first() {
+	    Some((coll,xs)) => (*coll,xs),
+	    None => return Value::PersistentVector(PersistentVector::from(vec![]))
+	};
+	let xs = xs.iter().map(|x| x.to_rc_value());
+	// nil conjs like the empty list 
+	let empty_list = Value::PersistentList(Empty(None));
+	let coll = if let Value::Nil = coll { &empty_list } else { coll };
+	match coll {
+	    Value::PersistentList(plist) => {
+		let mut conjed = plist.clone();
+		for x in xs {
+		    conjed = persistent_list::cons_rc(x,Rc::new(conjed));
+		}
+		Value::PersistentList(conjed)
+	    },
+	    Value::PersistentVector(pvector) => {
+		let mut vals = pvector.vals.clone();
+		vals.extend(xs);
+		Value::PersistentVector(PersistentVector { vals, meta: pvector.meta.clone() })
+	    },
+	    Value::PersistentHashSet(phashset) => Value::PersistentHashSet(xs.fold(phashset.clone(),|set,x| set.conj(x))),
+	    Value::PersistentListMap(plistmap) => {
+		let mut conjed = plistmap.clone();
+		for x in xs {
+		    match &*x {
+			Value::PersistentVector(PersistentVector { vals, .. }) if vals.len() == 2 => {
+			    conjed = conjed.assoc(Rc::clone(&vals[0]),Rc::clone(&vals[1]));
+			},
+			Value::PersistentListMap(entries) => {
+			    for MapEntry { key, val } in entries.iter() {
+				conjed = conjed.assoc(key,val);
+			    }
+			},
+			Value::Nil => {},
+			_ => return condition::raise(String::from("Vector arg to map conj must be a pair"))
+		    }
+		}
+		Value::PersistentListMap(conjed)
+	    },
+	    _ => condition::raise(format!("conj not supported on this type: {}",coll.type_tag()))
+	}
+    }
+}
+
+/// (disj #{1 2 3} 1 2) => #{3} 
+#[derive(Debug,Clone)]
+pub struct DisjFn {
+}
+impl ToValue for DisjFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for DisjFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	match args.split_first() {
+	    None => condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)")),
+	    Some((Value::Nil,_)) => Value::Nil,
+	    Some((set,ks)) => match set_arg(set) {
+		Ok(phashset) => Value::PersistentHashSet(ks.iter().fold(phashset.clone(),|set,k| set.disj(&k.to_rc_value()))),
+		Err(condition) => condition
+	    }
+	}
+    }
+}
+
+/// (contains? coll key)
+///
+/// Whether key is a key of a map,  a member of a set,  or an index of a vector or string 
+///    (contains? {:a nil} :a) => true,  (contains? [:a :b] 1) => true,  (contains? [:a :b] :a) => false
+#[derive(Debug,Clone)]
+pub struct ContainsFn {
+}
+impl ToValue for ContainsFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ContainsFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2)",args.len()));
+	}
+	let key = args[1];
+	let index_of = |len: usize| match Number::from_value(key) {
+	    Some(Number::Integer(ind)) => ind >= 0 && (ind as usize) < len,
+	    _ => false
+	};
+	let contains = match args[0] {
+	    Value::PersistentListMap(plistmap) => plistmap.contains_key(&key.to_rc_value()),
+	    Value::PersistentHashSet(phashset) => phashset.contains(&key.to_rc_value()),
+	    Value::PersistentVector(pvector) => index_of(pvector.vals.len()),
+	    Value::String(string) => index_of(string.chars().count()),
+	    Value::Nil => false,
+	    coll => return condition::raise(format!("contains? not supported on type: {}",coll.type_tag()))
+	};
+	Value::Boolean(contains)
+    }
+}
+
+/// (meta obj) => obj's metadata map,  or nil if it has none 
+#[derive(Debug,Clone)]
+pub struct MetaFn {
+}
+impl ToValue for MetaFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for MetaFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	args[0].meta().map_or(Value::Nil,|meta| meta.to_value())
+    }
+}
+
+/// obj with its metadata replaced by meta,  or the condition to return if it can't have any 
+fn with_meta(obj: &Value,meta: &Value) -> Value {
+    let meta = match meta {
+	Value::PersistentListMap(meta) => Some(Rc::new(meta.clone())),
+	Value::Nil => None,
+	_ => return condition::raise(format!("Type mismatch; Expected instance of clojure.lang.PersistentListMap,  Recieved type {}",meta.type_tag()))
+    };
+    obj.with_meta(meta).unwrap_or_else(|| condition::raise(format!("{} cannot be cast to clojure.lang.IObj",obj.type_tag())))
+}
+
+/// (with-meta obj map) => obj,  with map as its metadata 
+#[derive(Debug,Clone)]
+pub struct WithMetaFn {
+}
+impl ToValue for WithMetaFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for WithMetaFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2)",args.len()));
+	}
+	with_meta(args[0],args[1])
+    }
+}
+
+/// (vary-meta obj f & args) => obj,  with (apply f (meta obj) args) as its metadata 
+///    (vary-meta ^{:a 1} [] conj [:b 2]) => ^{:a 1 :b 2} []
+#[derive(Debug,Clone)]
+pub struct VaryMetaFn {
+}
+impl ToValue for VaryMetaFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for VaryMetaFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() < 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: >=2)",args.len()));
+	}
+	let f = match args[1].as_ifn() {
+	    Some(f) => f,
+	    None => return condition::raise(format!("Execution Error: {} cannot be cast to clojure.lang.IFn",args[1].type_tag()))
+	};
+	let meta = args[0].meta().map_or(Value::Nil,|meta| meta.to_value());
+	let mut f_args = vec![&meta];
+	f_args.extend(&args[2..]);
+	let new_meta = f.invoke(f_args);
+	if new_meta.is_unwinding() {
+	    return new_meta;
+	}
+	with_meta(args[0],&new_meta)
+    }
+}
+
+/// (hash x) => x's hash code;  equal values (whatever their metadata) hash the same 
+#[derive(Debug,Clone)]
+pub struct HashFn {
+}
+impl ToValue for HashFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for HashFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	let mut hasher = DefaultHasher::new();
+	args[0].hash(&mut hasher);
+	Value::I64(hasher.finish() as i64)
+    }
+}
+
+/// (read-string "(+ 1 2)") => (+ 1 2);  a string we can't read gives a reader-error condition,
+/// which can be caught like any other 
 #[derive(Debug,Clone)]
-pub struct StringPrintFn {
+pub struct ReadStringFn {
 }
-impl ToValue for StringPrintFn {
+impl ToValue for ReadStringFn {
     fn to_value(&self) -> Value {
         Value::IFn(Rc::new(self.clone()))
     }
 }
-impl IFn for StringPrintFn {
+impl IFn for ReadStringFn {
     fn invoke(&self,args: Vec<&Value>) -> Value {
-	
-        Value::String(args.into_iter().map(|arg|arg.to_string()).collect::<Vec<String>>().join(""))
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match args[0] {
+	    Value::String(string) => match reader::try_read(string.as_bytes()) {
+		Ok((_,form)) => form,
+		Err(err) => condition::signal(err.to_condition())
+	    },
+	    _ => condition::raise(format!("Type mismatch; Expected instance of rust.std.string.String, Recieved type {}",args[0].type_tag()))
+	}
     }
 }
 
-
+/// (read);  the next form from stdin,  which is where the REPL reads from too 
 #[derive(Debug,Clone)]
-pub struct AddFn {
+pub struct ReadFn {
 }
-impl ToValue for AddFn {
+impl ToValue for ReadFn {
     fn to_value(&self) -> Value {
         Value::IFn(Rc::new(self.clone()))
     }
 }
-impl IFn for AddFn {
+impl IFn for ReadFn {
     fn invoke(&self,args: Vec<&Value>) -> Value {
-        args.into_iter().fold(0_i32.to_value(),|a,b|  {
-            match a {
-                Value::I32(a_) => match b { 
-                    Value::I32(b_) =>  Value::I32(a_ + b_),
-                    _ =>  Value::Condition(format!("Type mismatch; Expecting: (i32 | i64 | f32 | f64), Found: {}",b.type_tag()))
-                },
-                _ => Value::Condition(format!("Type mismatch: Expecting: (i32 | i64 | f32 | f64), Found: {}",a.type_tag()))
-            }
-        })
+	if !args.is_empty() {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 0)",args.len()));
+	}
+	match repl::read_stdin() {
+	    Some(Ok(form)) => form,
+	    Some(Err(err)) => condition::signal(err.to_condition()),
+	    None => condition::raise(String::from("EOF while reading"))
+	}
     }
 }
 
+//
+// clojure.set 
+//
 
+/// (clojure.set/union #{1 2} #{2 3}) => #{1 2 3} 
 #[derive(Debug,Clone)]
-pub struct EvalFn {
-    enclosing_environment: Rc<Environment>
+pub struct UnionFn {
 }
-impl EvalFn {
-    pub fn new(enclosing_environment: Rc<Environment>) -> EvalFn {
-	EvalFn{enclosing_environment}
+impl ToValue for UnionFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
     }
 }
-impl ToValue for EvalFn {
+impl IFn for UnionFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	let mut union = PersistentHashSet::empty();
+	for arg in args {
+	    match set_arg(arg) {
+		Ok(phashset) => union = phashset.iter().fold(union,|union,val| union.conj(val)),
+		Err(condition) => return condition
+	    }
+	}
+	Value::PersistentHashSet(union)
+    }
+}
+
+/// (clojure.set/intersection #{1 2} #{2 3}) => #{2} 
+#[derive(Debug,Clone)]
+pub struct IntersectionFn {
+}
+impl ToValue for IntersectionFn {
     fn to_value(&self) -> Value {
         Value::IFn(Rc::new(self.clone()))
     }
 }
-impl IFn for EvalFn {
+impl IFn for IntersectionFn {
     fn invoke(&self,args: Vec<&Value>) -> Value {
-	// @TODO generalize arity exceptions, and other exceptions 
-	if args.len() != 1 {
-	    return Value::Condition(format!("Wrong number of arguments given to function (Given: {}, Expected: {})",args.len(),args.len()));
+	if args.is_empty() {
+	    return condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)"));
 	}
-	let arg = args.get(0).unwrap();
-	arg.eval(Rc::clone(&self.enclosing_environment))
+	let sets = match args.into_iter().map(set_arg).collect::<Result<Vec<&PersistentHashSet>,Value>>() {
+	    Ok(sets) => sets,
+	    Err(condition) => return condition
+	};
+	Value::PersistentHashSet(sets[0].iter().filter(|val| sets[1..].iter().all(|set| set.contains(val))).collect::<PersistentHashSet>())
     }
 }
 
-
+/// (clojure.set/difference #{1 2 3} #{2} #{3}) => #{1} 
 #[derive(Debug,Clone)]
-pub struct DoFn {
+pub struct DifferenceFn {
 }
-impl ToValue for DoFn {
+impl ToValue for DifferenceFn {
     fn to_value(&self) -> Value {
         Value::IFn(Rc::new(self.clone()))
     }
 }
-impl IFn for DoFn {
+impl IFn for DifferenceFn {
     fn invoke(&self,args: Vec<&Value>) -> Value {
-	// @TODO generalize arity exceptions, and other exceptions 
 	if args.is_empty() {
-	    return Value::Nil;
+	    return condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)"));
 	}
-	(*args.last().unwrap()).clone()
+	let sets = match args.into_iter().map(set_arg).collect::<Result<Vec<&PersistentHashSet>,Value>>() {
+	    Ok(sets) => sets,
+	    Err(condition) => return condition
+	};
+	Value::PersistentHashSet(sets[0].iter().filter(|val| !sets[1..].iter().any(|set| set.contains(val))).collect::<PersistentHashSet>())
     }
 }
 
-
-//
-// Since our macros currently expand and evaluate at the same time,  our `do` macro will be implemented
-// by expanding to a do-fn, which will just naturally evaluate all arguments, being a fn, and
-// return the last item
-// This will change when macros change
-//
+/// (clojure.set/select pred xset) => the members of xset pred is true of 
+///    (clojure.set/select pos? #{-1 1 2}) => #{1 2}
 #[derive(Debug,Clone)]
-pub struct DoMacro {
+pub struct SelectFn {
 }
-impl ToValue for DoMacro {
+impl ToValue for SelectFn {
     fn to_value(&self) -> Value {
-        Value::Macro(Rc::new(self.clone()))
+        Value::IFn(Rc::new(self.clone()))
     }
 }
-impl IFn for DoMacro {
+impl IFn for SelectFn {
     fn invoke(&self,args: Vec<&Value>) -> Value {
-	// @TODO generalize arity exceptions, and other exceptions 
-	if args.len() == 0 {
-	    return vec![Symbol::intern("do").to_rc_value(),Rc::new(Value::Nil)].into_list().to_value();
+	if args.len() != 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2)",args.len()));
+	}
+	let pred = match args[0].as_ifn() {
+	    Some(pred) => pred,
+	    None => return condition::raise(format!("Execution Error: {} cannot be cast to clojure.lang.IFn",args[0].type_tag()))
+	};
+	let xset = match set_arg(args[1]) {
+	    Ok(xset) => xset,
+	    Err(condition) => return condition
+	};
+	let mut selected = PersistentHashSet::empty();
+	for val in xset.iter() {
+	    let keep = pred.invoke(vec![&*val]);
+	    if keep.is_unwinding() {
+		return keep;
+	    }
+	    if keep.is_truthy() {
+		selected = selected.conj(val);
+	    }
 	}
-	// (do a b c) becomes (do-fn* a b c), so we need to copy a,b, and c for our new expression 
-	let args_for_ret_expr = args.iter().map(|arg| {
-	    arg.to_rc_value()
-	}).collect::<Vec<Rc<Value>>>();
-	
-	let mut do_body = vec![Symbol::intern("do-fn*").to_rc_value()];
-	do_body.extend_from_slice(args_for_ret_expr.get(0..).unwrap());
+	Value::PersistentHashSet(selected)
+    }
+}
 
-	do_body.into_list().to_value()
+/// Just the entries of map whose keys are in ks,  in the order of ks 
+fn select_keys(map: &Value,ks: &[Rc<Value>]) -> Value {
+    match map {
+	Value::PersistentListMap(plistmap) => {
+	    ks.iter()
+		.filter(|key| plistmap.contains_key(key))
+		.map(|key| MapEntry { key: Rc::clone(key), val: plistmap.get(key) })
+		.collect::<PersistentListMap>()
+		.to_value()
+	},
+	_ => PersistentListMap::Empty(None).to_value()
     }
 }
 
+/// (clojure.set/project xrel ks)
+///
+/// A relation (set of maps) of just the ks of each map in xrel 
+///    (clojure.set/project #{{:a 1 :b 2}} [:a]) => #{{:a 1}}
+#[derive(Debug,Clone)]
+pub struct ProjectFn {
+}
+impl ToValue for ProjectFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ProjectFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2)",args.len()));
+	}
+	match (coll_vals(args[0]),coll_vals(args[1])) {
+	    (Some(xrel),Some(ks)) => Value::PersistentHashSet(xrel.iter().map(|x| select_keys(x,&ks).to_rc_value()).collect::<PersistentHashSet>()),
+	    _ => condition::raise(format!("Don't know how to create ISeq from: {}",args.iter().find(|arg| coll_vals(arg).is_none()).unwrap().type_tag()))
+	}
+    }
+}
 
+/// (clojure.set/rename-keys map kmap)
+///
+/// map with each key in kmap renamed to what kmap maps it to 
+///    (clojure.set/rename-keys {:a 1 :b 2} {:a :c}) => {:c 1 :b 2}
 #[derive(Debug,Clone)]
-pub struct NthFn {
+pub struct RenameKeysFn {
 }
-impl ToValue for NthFn {
+impl ToValue for RenameKeysFn {
     fn to_value(&self) -> Value {
         Value::IFn(Rc::new(self.clone()))
     }
 }
-impl IFn for NthFn {
+impl IFn for RenameKeysFn {
     fn invoke(&self,args: Vec<&Value>) -> Value {
-	// @TODO generalize arity exceptions, and other exceptions 
 	if args.len() != 2 {
-	    return Value::Condition(format!("Wrong number of arguments (Given: {}, Expected: 1-2)",args.len()));
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2)",args.len()));
 	}
-	// @TODO change iteration to work with Value references, or even change invoke to work on Rc<..>
-	//       as we do everything else; surely we don't want to clone just to read from a collection 
-	if let Some(Value::I32(ind)) = args.get(1) {
-	    if *ind < 0 {
-		return Value::Condition(format!("Index cannot be negative; Index ({})",ind));
-	    }
-	    let ind = *ind as usize;
-	    
-	    match args.get(0).unwrap() {
-		Value::PersistentList(Cons(head,tail,count)) => {
-		    let count = *count as usize;
-		    if ind >= count {
-			Value::Condition(format!("Index out of bounds: Index ({}), Length: ({})",ind,count))
-		    }
-		    else if ind == 0 {
-			head.to_value()
-		    }
-		    else {
-			tail.iter().nth(ind - 1).unwrap().to_value()
-		    }
-		},
-		Value::PersistentList(Empty) => {
-		    Value::Condition(format!("Index out of bounds: Index ({}), Length: ({})",ind,0))
-		},
-		Value::PersistentVector(PersistentVector { vals }) => {
-		    if ind >= vals.len() {
-			Value::Condition(format!("Index out of bounds: Index ({}), Length: ({})",ind,vals.len()))
-		    }
-		    else {
-			vals.get(ind).unwrap().to_value()
+	match (args[0],args[1]) {
+	    (Value::PersistentListMap(map),Value::PersistentListMap(kmap)) => {
+		// First the entries we're not renaming,  then the ones we are,  so a renamed key
+		// replaces any key already by that name 
+		let mut entries = map.iter().filter(|entry| !kmap.contains_key(&entry.key)).collect::<Vec<MapEntry>>();
+		for MapEntry { key: old_key, val: new_key } in kmap.iter() {
+		    if map.contains_key(&old_key) {
+			entries.push(MapEntry { key: new_key, val: map.get(&old_key) });
 		    }
-		},
-	    _ => Value::Condition(format!("Type mismatch; Expected instance of clojure.lang.ISeq, Recieved type {}",args.get(0).unwrap().type_tag()))
+		}
+		Value::PersistentListMap(entries.into_iter().collect::<PersistentListMap>())
+	    },
+	    (Value::PersistentListMap(_),arg) | (arg,_) =>
+		condition::raise(format!("Type mismatch; Expected instance of clojure.lang.PersistentListMap,  Recieved type {}",arg.type_tag()))
+	}
+    }
+}
+
+/// (clojure.set/index xrel ks)
+///
+/// The maps in xrel,  grouped into sets by their values for ks 
+///    (clojure.set/index #{{:a 1 :b 2} {:a 1 :b 3}} [:a]) => {{:a 1} #{{:a 1 :b 2} {:a 1 :b 3}}}
+#[derive(Debug,Clone)]
+pub struct IndexFn {
+}
+impl ToValue for IndexFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for IndexFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2)",args.len()));
+	}
+	let (xrel,ks) = match (coll_vals(args[0]),coll_vals(args[1])) {
+	    (Some(xrel),Some(ks)) => (xrel,ks),
+	    _ => return condition::raise(format!("Don't know how to create ISeq from: {}",args.iter().find(|arg| coll_vals(arg).is_none()).unwrap().type_tag()))
+	};
+	// Our groups,  in the order we first came across them 
+	let mut groups : Vec<(Rc<Value>,PersistentHashSet)> = vec![];
+	for x in xrel {
+	    let group_key = select_keys(&x,&ks).to_rc_value();
+	    match groups.iter_mut().find(|(key,_)| *key == group_key) {
+		Some((_,group)) => *group = group.conj(x),
+		None => groups.push((group_key,PersistentHashSet::empty().conj(x)))
 	    }
 	}
-	else {
-	    Value::Condition(format!("Type mismatch; Expected instance of clojure.lang.Integer,  Recieved type {}",args.get(1).unwrap().type_tag()))
+	Value::PersistentListMap(groups.into_iter().map(|(key,group)| MapEntry { key, val: group.to_rc_value() }).collect::<PersistentListMap>())
+    }
+}
+
+/// (char 65) => \\A 
+#[derive(Debug,Clone)]
+pub struct CharFn {
+}
+impl ToValue for CharFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for CharFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	if let Value::Char(ch) = args[0] {
+	    return Value::Char(*ch);
+	}
+	match Number::from_value(args[0]) {
+	    Some(Number::Integer(code)) => u32::try_from(code).ok().and_then(std::char::from_u32).map_or_else(|| {
+		condition::raise(format!("Value out of range for char: {}",code))
+	    },Value::Char),
+	    _ => condition::raise(format!("Type mismatch; Expecting: (char | i64), Found: {}",args[0].type_tag()))
+	}
+    }
+}
+
+/// (int \\A) => 65,  (int 2.7) => 2 
+#[derive(Debug,Clone)]
+pub struct IntFn {
+}
+impl ToValue for IntFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for IntFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	if let Value::Char(ch) = args[0] {
+	    return Value::I64(i64::from(u32::from(*ch)));
 	}
+	unary_number_fn(args,|x| match x.truncate_to_bigint()?.demote() {
+	    Number::Integer(i) => Ok(Value::I64(i)),
+	    number => Err(numbers::arithmetic_condition(format!("Value out of range for int: {}",number.into_value())))
+	})
     }
 }
 
+#[derive(Debug,Clone)]
+pub struct IsCharFn {
+}
+impl ToValue for IsCharFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for IsCharFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	Value::Boolean(matches!(args[0],Value::Char(_)))
+    }
+}
 
 #[derive(Debug,Clone)]
-pub struct ConcatFn {
+pub struct IsStringFn {
 }
-impl ToValue for ConcatFn {
+impl ToValue for IsStringFn {
     fn to_value(&self) -> Value {
         Value::IFn(Rc::new(self.clone()))
     }
 }
-impl IFn for ConcatFn {
+impl IFn for IsStringFn {
     fn invoke(&self,args: Vec<&Value>) -> Value {
-	let concatted_vec = args.iter().fold(vec![],|mut sum,coll| {
-	    let coll_vec = match coll {
-		Value::PersistentList(plist) =>
-		    Rc::new(plist.clone()).iter().collect::<Vec<Rc<Value>>>(),
-		Value::PersistentVector(pvector) =>
-		    Rc::new(pvector.clone()).iter().collect::<Vec<Rc<Value>>>(),
-		_ => vec![]
-	    };
-	    
-	    sum.extend(coll_vec);
-	    sum	
-	});
-	Value::PersistentList(concatted_vec.into_iter().collect::<PersistentList>())
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	Value::Boolean(matches!(args[0],Value::String(_)))
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct IsMapFn {
+}
+impl ToValue for IsMapFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for IsMapFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	Value::Boolean(matches!(args[0],Value::PersistentListMap(_)))
     }
 }
 
@@ -251,9 +1481,200 @@ impl ToValue for PrintStringFn {
 impl IFn for PrintStringFn {
     fn invoke(&self,args: Vec<&Value>) -> Value {
 	if args.len() != 1 {
-	    return Value::Condition(format!("Wrong number of arguments given to function (Given: {}, Expected: {})",args.len(),args.len()));
+	    return condition::raise(format!("Wrong number of arguments given to function (Given: {}, Expected: {})",args.len(),args.len()));
 	}
-	println!("{}",args.get(0).unwrap().to_string());
+	println!("{}",args.first().unwrap());
 	Value::Nil 
     }
 }
+
+/// (error type)
+/// (error type)
+/// (error type data)
+///
+/// Signals a condition of type `type`,  a keyword,  carrying the map `data`,  to our handlers
+/// (see condition.rs),  evaluating to the restart they choose,  or to the condition itself if
+/// none do 
+#[derive(Debug,Clone)]
+pub struct ErrorFn {
+}
+impl ToValue for ErrorFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ErrorFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.is_empty() || args.len() > 2 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1-2)",args.len()));
+	}
+	let condition_type = match args[0] {
+	    Value::Keyword(_) => args[0].to_rc_value(),
+	    _ => return condition::raise(format!("Type mismatch; Expected instance of clojure.lang.Keyword, Recieved type {}",args[0].type_tag()))
+	};
+	let data = match args.get(1) {
+	    Some(Value::PersistentListMap(_)) => args[1].to_rc_value(),
+	    Some(Value::Nil) | None => Rc::new(Value::Nil),
+	    Some(data) => return condition::raise(format!("Type mismatch; Expected instance of clojure.lang.PersistentListMap, Recieved type {}",data.type_tag()))
+	};
+	let message = match &*data {
+	    Value::Nil => condition_type.to_string(),
+	    _ => format!("{} {}",condition_type,data.to_string_explicit())
+	};
+	condition::signal(Condition {
+	    condition_type,
+	    data,
+	    ..Condition::new(message)
+	})
+    }
+}
+
+/// (invoke-restart name & args)
+///
+/// Transfers control to the innermost active restart named `name`,  giving it `args`
+#[derive(Debug,Clone)]
+pub struct InvokeRestartFn {
+}
+impl ToValue for InvokeRestartFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for InvokeRestartFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	let name = match args.first() {
+	    Some(name) => name,
+	    None => return condition::raise(String::from("Wrong number of arguments (Given: 0, Expected: >=1)"))
+	};
+	let restart = condition::restart_name(name).and_then(|name| condition::find_restart(&name));
+	match restart {
+	    Some(restart) => {
+		let restart_args = args.iter().skip(1).map(|arg| arg.to_rc_value()).collect::<Vec<Rc<Value>>>();
+		Value::RestartInvocation(Rc::new(restart.invocation(restart_args)))
+	    },
+	    None => condition::raise(format!("No restart named {} is active",name))
+	}
+    }
+}
+
+/// (ex-info msg map)
+/// (ex-info msg map cause)
+///
+/// Makes a condition carrying map,  to be held onto rather than unwinding (yet)
+#[derive(Debug,Clone)]
+pub struct ExInfoFn {
+}
+impl ToValue for ExInfoFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ExInfoFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() < 2 || args.len() > 3 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2-3)",args.len()));
+	}
+	let message = match args[0] {
+	    Value::String(message) => message.clone(),
+	    _ => return condition::raise(format!("Type mismatch; Expected instance of rust.std.string.String, Recieved type {}",args[0].type_tag()))
+	};
+	let data = match args[1] {
+	    Value::PersistentListMap(_) => args[1].to_rc_value(),
+	    _ => return condition::raise(format!("Type mismatch; Expected instance of clojure.lang.PersistentListMap, Recieved type {}",args[1].type_tag()))
+	};
+	let cause = match args.get(2) {
+	    Some(Value::HeldCondition(cause)) => Some(Rc::new(cause.clone())),
+	    Some(Value::Nil) | None => None,
+	    Some(cause) => return condition::raise(format!("Type mismatch; Expected instance of clojure.lang.Condition, Recieved type {}",cause.type_tag()))
+	};
+	Value::HeldCondition(Condition {
+	    data,
+	    cause,
+	    ..Condition::new(message)
+	})
+    }
+}
+
+/// (ex-data condition) => the map condition carries,  or nil if it's not a condition
+#[derive(Debug,Clone)]
+pub struct ExDataFn {
+}
+impl ToValue for ExDataFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ExDataFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match args[0] {
+	    Value::HeldCondition(condition) => condition.data.to_value(),
+	    _ => Value::Nil
+	}
+    }
+}
+
+/// (ex-message condition) => condition's message,  or nil if it's not a condition
+#[derive(Debug,Clone)]
+pub struct ExMessageFn {
+}
+impl ToValue for ExMessageFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ExMessageFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match args[0] {
+	    Value::HeldCondition(condition) => Value::String(condition.message.clone()),
+	    _ => Value::Nil
+	}
+    }
+}
+
+/// (ex-cause condition) => the condition that caused condition,  or nil if none did
+#[derive(Debug,Clone)]
+pub struct ExCauseFn {
+}
+impl ToValue for ExCauseFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for ExCauseFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match args[0] {
+	    Value::HeldCondition(Condition { cause: Some(cause), .. }) => Value::HeldCondition((**cause).clone()),
+	    _ => Value::Nil
+	}
+    }
+}
+
+/// (inst-ms #inst "1970-01-01T00:00:01Z") => 1000;  milliseconds since the epoch 
+#[derive(Debug,Clone)]
+pub struct InstMsFn {
+}
+impl ToValue for InstMsFn {
+    fn to_value(&self) -> Value {
+        Value::IFn(Rc::new(self.clone()))
+    }
+}
+impl IFn for InstMsFn {
+    fn invoke(&self,args: Vec<&Value>) -> Value {
+	if args.len() != 1 {
+	    return condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()));
+	}
+	match args[0] {
+	    Value::Inst(inst) => Value::I64(inst.millis),
+	    _ => condition::raise(format!("Type mismatch; Expected instance of clojure.lang.Inst, Recieved type {}",args[0].type_tag()))
+	}
+    }
+}
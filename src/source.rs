@@ -0,0 +1,154 @@
+//! Where our forms came from
+//!
+//! Reading from a Source (see `reader::try_read_source`),  rather than from bare bytes, lets
+//! the reader tag each list it reads with the :file, :line and :column it started at,  as
+//! metadata.  When something then goes wrong evaluating that list,  we can say where it was,
+//! and quote that line of source back with a caret under the spot:
+//!
+//!   at (+ x y) src/clojure/core.clj:12:3
+//!       (+ x y))
+//!       ^
+use crate::value::{Value,ToValue};
+use crate::keyword::Keyword;
+use crate::maps::MapEntry;
+use crate::persistent_list_map::{PersistentListMap,IPersistentListMap};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::fmt;
+
+thread_local! {
+    // The latest source read under each name,  which we quote from
+    static SOURCES: RefCell<HashMap<Rc<str>,Rc<Source>>> = RefCell::new(HashMap::new());
+}
+
+/// Text we read forms out of,  and what to call it when we say where they came from
+#[derive(Debug)]
+pub struct Source {
+    pub name: Rc<str>,
+    pub text: Rc<str>,
+    // The line our text starts on;  the text a stream is read from (see LispReader) lets go
+    // of the lines it's done with
+    first_line: usize,
+    // Where in our text each line starts
+    line_starts: Vec<usize>
+}
+impl Source {
+    /// The source named name,  whose text is text;  this replaces whatever source we knew by
+    /// that name before
+    pub fn new(name: &str,text: &str) -> Rc<Source> {
+	Source::starting_at_line(name,text,1)
+    }
+    /// Like new,  for text that's the rest of our source from line first_line on 
+    pub fn starting_at_line(name: &str,text: &str,first_line: usize) -> Rc<Source> {
+	let line_starts = std::iter::once(0)
+	    .chain(text.match_indices('\n').map(|(ind,_)| ind + 1))
+	    .collect();
+	let source = Rc::new(Source { name: Rc::from(name), text: Rc::from(text), first_line, line_starts });
+	SOURCES.with(|sources| sources.borrow_mut().insert(Rc::clone(&source.name),Rc::clone(&source)));
+	source
+    }
+    /// Where in our text input starts,  if input is a slice of our text at all
+    pub fn offset_of(&self,input: &[u8]) -> Option<usize> {
+	let start = self.text.as_ptr() as usize;
+	let input_start = input.as_ptr() as usize;
+	if input_start >= start && input_start + input.len() <= start + self.text.len() {
+	    Some(input_start - start)
+	}
+	else {
+	    None
+	}
+    }
+    /// The line and column of offset,  counting both from 1 (and columns in chars)
+    pub fn position_at(&self,offset: usize) -> SourcePosition {
+	let line_ind = match self.line_starts.binary_search(&offset) {
+	    Ok(line_ind) => line_ind,
+	    Err(next_line_ind) => next_line_ind - 1
+	};
+	let line_start = self.line_starts[line_ind];
+	SourcePosition {
+	    file: Rc::clone(&self.name),
+	    line: line_ind + self.first_line,
+	    column: self.text[line_start..offset].chars().count() + 1
+	}
+    }
+    /// The position input starts at,  if it's a slice of our text
+    pub fn position_of(&self,input: &[u8]) -> Option<SourcePosition> {
+	self.offset_of(input).map(|offset| self.position_at(offset))
+    }
+    /// Line number line of our text,  without its newline
+    fn line(&self,line: usize) -> Option<&str> {
+	let line_ind = line.checked_sub(self.first_line)?;
+	let start = *self.line_starts.get(line_ind)?;
+	let end = self.line_starts.get(line_ind + 1).map_or(self.text.len(),|next_start| next_start - 1);
+	Some(self.text[start..end].trim_end_matches('\r'))
+    }
+}
+
+/// Where in a source a form (or a reader error) is
+#[derive(Debug,Clone,PartialEq)]
+pub struct SourcePosition {
+    pub file: Rc<str>,
+    pub line: usize,
+    pub column: usize
+}
+impl SourcePosition {
+    /// The position form was read from,  if it was read from a source
+    pub fn of_form(form: &Value) -> Option<SourcePosition> {
+	let meta = form.meta()?;
+	let get = |key: &str| meta.get(&Keyword::intern(key).to_rc_value());
+	match (&*get("file"),&*get("line"),&*get("column")) {
+	    (Value::String(file),Value::I64(line),Value::I64(column)) => Some(SourcePosition {
+		file: Rc::from(file.as_str()),
+		line: *line as usize,
+		column: *column as usize
+	    }),
+	    _ => None
+	}
+    }
+    /// {:file "core.clj" :line 1 :column 1};  what the reader gives a form as metadata
+    pub fn to_meta(&self) -> PersistentListMap {
+	vec![MapEntry { key: Keyword::intern("file").to_rc_value(), val: Value::String(self.file.to_string()).to_rc_value() },
+	     MapEntry { key: Keyword::intern("line").to_rc_value(), val: Value::I64(self.line as i64).to_rc_value() },
+	     MapEntry { key: Keyword::intern("column").to_rc_value(), val: Value::I64(self.column as i64).to_rc_value() }]
+	    .into_iter().collect()
+    }
+    /// Our line of source,  and a caret under our column beneath it,  if we still have the source
+    pub fn excerpt(&self) -> Option<String> {
+	let source = SOURCES.with(|sources| sources.borrow().get(&self.file).cloned())?;
+	let line = source.line(self.line)?;
+	// Tabs stay tabs,  so our caret lines up however wide they're shown
+	let padding = line.chars().take(self.column - 1).map(|ch| if ch == '\t' { '\t' } else { ' ' }).collect::<String>();
+	Some(format!("{}\n{}^",line,padding))
+    }
+}
+impl fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	write!(f,"{}:{}:{}",self.file,self.line,self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source::{Source,SourcePosition};
+
+    #[test]
+    fn test_positions_and_excerpts()
+    {
+	let source = Source::new("test.clj","(def x 1)\n\t(ƒ x\n  y)");
+	let text = source.text.as_bytes();
+	let position = source.position_of(&text[14..]).unwrap();
+	assert_eq!(position.to_string(),"test.clj:2:4");
+	assert_eq!(position.excerpt().unwrap(),"\t(ƒ x\n\t  ^");
+	assert_eq!(source.position_at(0).to_string(),"test.clj:1:1");
+	assert_eq!(source.position_at(text.len()).to_string(),"test.clj:3:5");
+	assert_eq!(source.position_of(b"(def x 1)"),None);
+	// The rest of a source,  from its third line on 
+	let rest = Source::starting_at_line("test.clj","  y)\n(z)",3);
+	let position = rest.position_of(&rest.text.as_bytes()[5..]).unwrap();
+	assert_eq!(position.to_string(),"test.clj:4:1");
+	assert_eq!(position.excerpt().unwrap(),"(z)\n^");
+	assert_eq!(SourcePosition { line: 2, ..position }.excerpt(),None);
+    }
+}
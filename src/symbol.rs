@@ -1,18 +1,111 @@
+use crate::meta::{IMeta,IObj};
+use crate::persistent_list_map::PersistentListMap;
+
 use std::hash::{Hash,Hasher};
 use std::fmt;
+use std::cell::Cell;
+use std::rc::Rc;
+
+thread_local! {
+    static NEXT_GENSYM_ID: Cell<usize> = const { Cell::new(0) };
+}
 
-#[derive(Hash,PartialEq,Eq,Clone,Debug)]
+#[derive(Clone,Debug)]
 pub struct Symbol {
-    pub name: String
+    /// The namespace we're qualified with;  clojure.string,  for clojure.string/join 
+    pub ns: Option<String>,
+    pub name: String,
+    meta: Option<Rc<PersistentListMap>>
 }
 impl Symbol {
-    pub fn intern(name: &str) -> Symbol 
+    /// The symbol for full_name;  name,  or ns/name.  The first / is what splits the two,  so
+    /// / alone is just a name,  and clojure.core// is / in clojure.core
+    pub fn intern(full_name: &str) -> Symbol 
+    {
+	match full_name.split_once('/') {
+	    Some((ns,name)) if full_name != "/" => Symbol::intern_with_ns(ns,name),
+	    _ => Symbol { ns: None, name: String::from(full_name), meta: None }
+	}
+    }
+    /// ns/name 
+    pub fn intern_with_ns(ns: &str,name: &str) -> Symbol
+    {
+	Symbol { ns: Some(String::from(ns)), name: String::from(name), meta: None }
+    }
+    /// ns/name => name;  the symbol we'd find this one under,  in its namespace 
+    pub fn unqualified(&self) -> Symbol
+    {
+	Symbol { ns: None, ..self.clone() }
+    }
+    /// Whether this is a symbol we could have read;  a namespace and name that aren't empty,
+    /// and a name without a / of its own (unless it's just /)
+    pub fn is_valid(&self) -> bool
     {
-	Symbol {name: String::from(name)}
+	let valid_name = self.name == "/" || (!self.name.is_empty() && !self.name.contains('/'));
+	valid_name && self.ns.as_ref().is_none_or(|ns| !ns.is_empty())
+    }
+    /// A symbol unlike any other read or generated so far;  gensym("x") => x__12
+    pub fn gensym(prefix: &str) -> Symbol
+    {
+	let id = NEXT_GENSYM_ID.with(|id| {
+	    let next_id = id.get();
+	    id.set(next_id + 1);
+	    next_id
+	});
+	Symbol::intern(&format!("{}__{}",prefix,id))
+    }
+}
+// Our metadata is just along for the ride;  ^:private x is still x 
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Symbol) -> bool {
+	self.ns == other.ns && self.name == other.name
+    }
+}
+impl Eq for Symbol {}
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+	self.ns.hash(state);
+	self.name.hash(state);
+    }
+}
+impl IMeta for Symbol {
+    fn meta(&self) -> Option<Rc<PersistentListMap>> {
+	self.meta.clone()
+    }
+}
+impl IObj for Symbol {
+    fn with_meta(&self,meta: Option<Rc<PersistentListMap>>) -> Symbol {
+	Symbol { meta, ..self.clone() }
     }
 }
 impl fmt::Display for Symbol {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-	write!(f,"{}",self.name)
+	match &self.ns {
+	    Some(ns) => write!(f,"{}/{}",ns,self.name),
+	    None => write!(f,"{}",self.name)
+	}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::symbol::Symbol;
+
+    #[test]
+    fn test_intern()
+    {
+	let join = Symbol::intern("clojure.string/join");
+	assert_eq!(join.ns.as_deref(),Some("clojure.string"));
+	assert_eq!(join.name,"join");
+	assert_eq!(join,Symbol::intern_with_ns("clojure.string","join"));
+	assert_ne!(join,Symbol::intern("join"));
+	assert_eq!(join.unqualified(),Symbol::intern("join"));
+	assert_eq!(join.to_string(),"clojure.string/join");
+	assert_eq!(Symbol::intern("/").ns,None);
+	assert_eq!(Symbol::intern("clojure.core//").name,"/");
+	assert!(Symbol::intern("foo.bar").is_valid());
+	assert!(!Symbol::intern("/foo").is_valid());
+	assert!(!Symbol::intern("foo/").is_valid());
+	assert!(!Symbol::intern("a/b/c").is_valid());
     }
 }
@@ -3,15 +3,25 @@ use std::fmt;
 #[derive(Debug,Clone)]
 pub enum TypeTag {
     I32,
+    I64,
+    BigInt,
+    Ratio,
+    F64,
     Symbol,
+    Keyword,
+    Char,
+    Inst,
+    Uuid,
     IFn,
     Condition,
     PersistentList,
     PersistentVector,
     PersistentListMap,
+    PersistentHashSet,
     // Experimental; may make no sense at runtime, as we will likely be unable to take the value of a macro 
     Macro,
     String,
+    Boolean,
     Nil
 }
 use TypeTag::*;
@@ -20,14 +30,24 @@ impl fmt::Display for TypeTag {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 	let str = match self {
 	    I32 => std::string::String::from("rust.std.i32"),
+	    I64 => std::string::String::from("rust.std.i64"),
+	    BigInt => std::string::String::from("clojure.lang.BigInt"),
+	    Ratio => std::string::String::from("clojure.lang.Ratio"),
+	    F64 => std::string::String::from("rust.std.f64"),
 	    Symbol => std::string::String::from("clojure.lang.Symbol"),
+	    Keyword => std::string::String::from("clojure.lang.Keyword"),
+	    Char => std::string::String::from("rust.std.char"),
+	    Inst => std::string::String::from("clojure.lang.Inst"),
+	    Uuid => std::string::String::from("clojure.lang.UUID"),
 	    IFn => std::string::String::from("clojure.lang.Function"),
 	    Condition => std::string::String::from("clojure.lang.Condition"),
 	    PersistentList => std::string::String::from("clojure.lang.PersistentList"),
 	    PersistentVector => std::string::String::from("clojure.lang.PersistentVector"),
 	    PersistentListMap => std::string::String::from("clojure.lang.PersistentListMap"),
+	    PersistentHashSet => std::string::String::from("clojure.lang.PersistentHashSet"),
 	    Macro => std::string::String::from("clojure.lang.Macro"),
 	    TypeTag::String => std::string::String::from("rust.std.string.String"),
+	    Boolean => std::string::String::from("rust.std.bool"),
 	    Nil => std::string::String::from("clojure.lang.Nil")
 	};
 	write!(f, "{}",str)
@@ -0,0 +1,46 @@
+//! UUIDs;  #uuid "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"
+//!
+//! We don't generate or interpret them,  just read,  compare and print them,  so a UUID
+//! is just its 128 bits
+use std::fmt;
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,PartialOrd,Ord)]
+pub struct Uuid(pub u128);
+impl Uuid {
+    /// Parses the usual 8-4-4-4-12 hex digit form,  in either case,  or None if uuid isn't in it
+    pub fn parse(uuid: &str) -> Option<Uuid> {
+	let groups = uuid.split('-').collect::<Vec<&str>>();
+	let group_lens = groups.iter().map(|group| group.len()).collect::<Vec<usize>>();
+	if group_lens != [8,4,4,4,12] || !groups.iter().all(|group| group.bytes().all(|byte| byte.is_ascii_hexdigit())) {
+	    return None;
+	}
+	u128::from_str_radix(&groups.concat(),16).ok().map(Uuid)
+    }
+    /// Our 8-4-4-4-12 form,  in lowercase
+    pub fn hyphenated(&self) -> String {
+	let hex = format!("{:032x}",self.0);
+	format!("{}-{}-{}-{}-{}",&hex[..8],&hex[8..12],&hex[12..16],&hex[16..20],&hex[20..])
+    }
+}
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+	write!(f,"#uuid \"{}\"",self.hyphenated())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::uuid::Uuid;
+
+    #[test]
+    fn test_parse_uuid()
+    {
+	let uuid = Uuid::parse("F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6").unwrap();
+	assert_eq!(uuid.hyphenated(),"f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+	assert_eq!(Uuid::parse(&uuid.hyphenated()),Some(uuid));
+	assert_eq!(Uuid::parse("00000000-0000-0000-0000-000000000001"),Some(Uuid(1)));
+	assert_eq!(Uuid::parse("f81d4fae7dec11d0a76500a0c91e6bf6"),None);
+	assert_eq!(Uuid::parse("f81d4fae-7dec-11d0-a765-00a0c91e6bfg"),None);
+	assert_eq!(Uuid::parse("+81d4fae-7dec-11d0-a765-00a0c91e6bf6"),None);
+    }
+}
@@ -1,26 +1,32 @@
 use crate::symbol::Symbol;
+use crate::keyword::Keyword;
 use crate::type_tag::TypeTag;
 use crate::environment::Environment;
 use crate::ifn::IFn;
+use crate::ifn::{Invocation,RecursionPoint};
+use crate::ifn::Invocation::{Done,TailCall,Enter,Recur};
 use crate::persistent_list::{ToPersistentList,ToPersistentListIter,PersistentList};
-use crate::persistent_list::PersistentList::{Empty,Cons};
-use crate::persistent_vector::{ToPersistentVector,ToPersistentVectorIter,PersistentVector};
-use crate::persistent_list_map::{PersistentListMap,ToPersistentListMapIter};
+use crate::persistent_list::PersistentList::Cons;
+use crate::persistent_vector::PersistentVector;
+use crate::persistent_list_map::{PersistentListMap,IPersistentListMap,ToPersistentListMap,ToPersistentListMapIter};
+use crate::persistent_hash_set::PersistentHashSet;
+use crate::inst;
+use crate::uuid;
+use crate::meta::{self,IMeta,IObj,FnWithMeta};
 use crate::lambda;
 use crate::maps::MapEntry;
+use crate::condition;
+use crate::condition::{Condition,Handler,Restart,RestartInvocation};
 
 extern crate rand;
 use rand::Rng;
+use num_traits::ToPrimitive;
 
-use std::collections::HashMap;
 use std::hash::{Hash,Hasher};
 use std::rc::Rc;
 use std::fmt::Debug;
-use std::iter::FromIterator;
 use std::fmt;
 
-use std::ops::Deref;
-
 // @TODO Change IFn's name -- IFn is a function, not an IFn.
 //       The body it executes just happens to be an the IFn.  
 /// Represents any Value known to ClojureRS, by wrapping any Value known to ClojureRS;
@@ -30,7 +36,17 @@ use std::ops::Deref;
 #[derive(Debug,Clone)]
 pub enum Value {
     I32(i32),
+    I64(i64),
+    // Our arbitrary precision integers and ratios;  123N, 1/3.  A ratio is always kept in
+    // lowest terms,  with a denominator other than 1 
+    BigInt(num_bigint::BigInt),
+    Ratio(num_rational::BigRational),
+    F64(f64),
     Symbol(Symbol),
+    Keyword(Keyword),
+    Char(char),
+    Inst(inst::Inst),
+    Uuid(uuid::Uuid),
     IFn(Rc<dyn IFn>),
     //
     // Special case functions
@@ -43,8 +59,14 @@ pub enum Value {
     PersistentList(PersistentList),
     PersistentVector(PersistentVector),
     PersistentListMap(PersistentListMap),
+    PersistentHashSet(PersistentHashSet),
     
-    Condition(std::string::String),
+    Condition(Condition),
+    // A condition held as a plain value -- made with ex-info, or given to a handler -- rather
+    // than one unwinding 
+    HeldCondition(Condition),
+    // A handler chose a restart;  unwinding back up to the restart-case that established it 
+    RestartInvocation(Rc<RestartInvocation>),
     // Macro body is still a function, that will be applied to our unevaled arguments 
     Macro(Rc<dyn IFn>),
     //
@@ -55,8 +77,17 @@ pub enum Value {
     DefMacro,
     FnMacro,
     LetMacro,
-   
+    IfMacro,
+    DoMacro,
+    LoopMacro,
+    RecurMacro,
+    HandlerBindMacro,
+    RestartCaseMacro,
+    TryMacro,
+    ThrowMacro,
+
     String(std::string::String),
+    Boolean(bool),
     Nil
 }
 use crate::value::Value::*;
@@ -68,22 +99,60 @@ impl PartialEq for Value {
     //       our trait objects in IFn and Macro
     // @TODO implement our generic IFns some other way? After all, again, this isn't Java 
     fn eq(&self, other: &Value) -> bool {
-	// 
-	if let I32(i) = self {
-	    if let I32(i2) = other {
+	// Integers are equal whatever their size;  1 and 1N alike 
+	if let Some(i) = canonical_integer(self) {
+	    if let Some(i2) = canonical_integer(other) {
 		return i == i2 
 	    }    
 	}
 
+	if let Ratio(r) = self {
+	    if let Ratio(r2) = other {
+		return r == r2 
+	    }    
+	}
+
+	// As values (say,  as keys in a map) floats are equal when they hash the same,  so NaN
+	// is equal to itself;  (= ##NaN ##NaN) itself is left to numbers::equiv 
+	if let F64(f) = self {
+	    if let F64(f2) = other {
+		return canonical_f64_bits(*f) == canonical_f64_bits(*f2)
+	    }    
+	}
+
 	if let Symbol(sym) = self {
 	    if let Symbol(sym2) = other {
 		return sym == sym2;
 	    }
 	}
+
+	if let Keyword(keyword) = self {
+	    if let Keyword(keyword2) = other {
+		return keyword == keyword2;
+	    }
+	}
+
+	if let Char(ch) = self {
+	    if let Char(ch2) = other {
+		return ch == ch2;
+	    }
+	}
+
+	if let Inst(inst) = self {
+	    if let Inst(inst2) = other {
+		return inst == inst2;
+	    }
+	}
+
+	if let Uuid(uuid) = self {
+	    if let Uuid(uuid2) = other {
+		return uuid == uuid2;
+	    }
+	}
 	// Equality not defined on functions, similar to Clojure
 	// Change this perhaps? Diverge?
-	if let IFn(ifn) = self {
-	    if let IFn(ifn2) = other {
+	if let IFn(_) = self {
+	    if let IFn(_) = other {
 		return false;
 	    }
 	}
@@ -112,9 +181,27 @@ impl PartialEq for Value {
 	    }
 	}
 
-	if let Condition(msg) = self {
-	    if let Condition(msg2) = other {
-		return msg == msg2;
+	if let PersistentHashSet(phashset) = self {
+	    if let PersistentHashSet(phashset2) = other {
+		return *phashset == *phashset2;
+	    }
+	}
+
+	if let Condition(condition) = self {
+	    if let Condition(condition2) = other {
+		return condition == condition2;
+	    }
+	}
+
+	if let HeldCondition(condition) = self {
+	    if let HeldCondition(condition2) = other {
+		return condition == condition2;
+	    }
+	}
+
+	if let RestartInvocation(invocation) = self {
+	    if let RestartInvocation(invocation2) = other {
+		return invocation == invocation2;
 	    }
 	}
 
@@ -142,12 +229,66 @@ impl PartialEq for Value {
 	    }
 	}
 
+	if let IfMacro = self {
+	    if let IfMacro = other {
+		return true;
+	    }
+	}
+
+	if let DoMacro = self {
+	    if let DoMacro = other {
+		return true;
+	    }
+	}
+
+	if let LoopMacro = self {
+	    if let LoopMacro = other {
+		return true;
+	    }
+	}
+
+	if let RecurMacro = self {
+	    if let RecurMacro = other {
+		return true;
+	    }
+	}
+
+	if let HandlerBindMacro = self {
+	    if let HandlerBindMacro = other {
+		return true;
+	    }
+	}
+
+	if let RestartCaseMacro = self {
+	    if let RestartCaseMacro = other {
+		return true;
+	    }
+	}
+
+	if let TryMacro = self {
+	    if let TryMacro = other {
+		return true;
+	    }
+	}
+
+	if let ThrowMacro = self {
+	    if let ThrowMacro = other {
+		return true;
+	    }
+	}
+
 	if let String(string) = self {
 	    if let String(string2) = other {
 		return string == string2;
 	    }
 	}
 
+	if let Boolean(b) = self {
+	    if let Boolean(b2) = other {
+		return b == b2;
+	    }
+	}
+
 	if let Nil = self {
 	    if let Nil = other {
 		return true;
@@ -159,6 +300,29 @@ impl PartialEq for Value {
     }
 }
 
+/// An integer,  of whatever size,  as an i64 if it fits in one and as a BigInt only if it
+/// doesn't;  None if value isn't an integer
+fn canonical_integer(value: &Value) -> Option<Result<i64,num_bigint::BigInt>> {
+    match value {
+	I32(i) => Some(Ok(i64::from(*i))),
+	I64(i) => Some(Ok(*i)),
+	BigInt(i) => Some(i.to_i64().ok_or_else(|| i.clone())),
+	_ => None
+    }
+}
+/// The bits of f,  with -0.0 as 0.0 and every NaN as the one NaN
+fn canonical_f64_bits(f: f64) -> u64 {
+    if f == 0.0 {
+	0.0f64.to_bits()
+    }
+    else if f.is_nan() {
+	f64::NAN.to_bits()
+    }
+    else {
+	f.to_bits()
+    }
+}
+
 // Again, this is certainly not the right away to do this
 // @FIXME remove this entire monstrocity 
 #[derive(Debug,Clone,Hash)]
@@ -169,14 +333,28 @@ enum ValueHash {
     DefMacro,
     FnMacro,
     LetMacro,
+    IfMacro,
+    DoMacro,
+    LoopMacro,
+    RecurMacro,
+    HandlerBindMacro,
+    RestartCaseMacro,
+    TryMacro,
+    ThrowMacro,
     Nil
 }
 impl Eq for Value {}
 impl Hash for Value {
     fn hash<H: Hasher>(&self, state: &mut H) {
 	match self {
-	    I32(i) => i.hash(state),
+	    I32(_) | I64(_) | BigInt(_) => canonical_integer(self).hash(state),
+	    Ratio(r) => r.hash(state),
+	    F64(f) => canonical_f64_bits(*f).hash(state),
 	    Symbol(sym) => sym.hash(state),
+	    Keyword(keyword) => keyword.hash(state),
+	    Char(ch) => ch.hash(state),
+	    Inst(inst) => inst.hash(state),
+	    Uuid(uuid) => uuid.hash(state),
 	    IFn(_) => {
 		let mut rng = rand::thread_rng();
 		let n2: u16 = rng.gen();
@@ -186,7 +364,10 @@ impl Hash for Value {
 	    PersistentList(plist) => plist.hash(state),
 	    PersistentVector(pvector) => pvector.hash(state),
 	    PersistentListMap(plistmap) => plistmap.hash(state),
-	    Condition(msg) => msg.hash(state),
+	    PersistentHashSet(phashset) => phashset.hash(state),
+	    Condition(condition) => condition.hash(state),
+	    HeldCondition(condition) => condition.hash(state),
+	    RestartInvocation(invocation) => invocation.hash(state),
 	    // Random hash is temporary;
 	    // @TODO implement hashing for functions / macros 
 	    Macro(_) => {
@@ -199,8 +380,17 @@ impl Hash for Value {
 	    DefMacro => ValueHash::DefMacro.hash(state),
 	    FnMacro => ValueHash::FnMacro.hash(state),
 	    LetMacro => ValueHash::LetMacro.hash(state),
+	    IfMacro => ValueHash::IfMacro.hash(state),
+	    DoMacro => ValueHash::DoMacro.hash(state),
+	    LoopMacro => ValueHash::LoopMacro.hash(state),
+	    RecurMacro => ValueHash::RecurMacro.hash(state),
+	    HandlerBindMacro => ValueHash::HandlerBindMacro.hash(state),
+	    RestartCaseMacro => ValueHash::RestartCaseMacro.hash(state),
+	    TryMacro => ValueHash::TryMacro.hash(state),
+	    ThrowMacro => ValueHash::ThrowMacro.hash(state),
 
 	    String(string) => string.hash(state),
+	    Boolean(b) => b.hash(state),
 	    Nil => ValueHash::Nil.hash(state),
 	}
          // self.id.hash(state);
@@ -211,20 +401,52 @@ impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 	let str = match self {
 	    I32(val) => val.to_string(),
+	    I64(val) => val.to_string(),
+	    BigInt(val) => format!("{}N",val),
+	    Ratio(val) => val.to_string(),
+	    F64(val) => {
+		if val.is_nan() {
+		    std::string::String::from("##NaN")
+		}
+		else if val.is_infinite() {
+		    std::string::String::from(if *val > 0.0 { "##Inf" } else { "##-Inf" })
+		}
+		else {
+		    // Debug keeps the .0 on a whole float,  so 1.0 doesn't print as the integer 1 
+		    format!("{:?}",val)
+		}
+	    },
 	    Symbol(sym) => sym.to_string(),
+	    Keyword(keyword) => keyword.to_string(),
+	    Char(ch) => ch.to_string(),
+	    // (str uuid) is just its hex digits,  as in Clojure;  printed,  it's a #uuid literal again 
+	    Inst(inst) => inst.timestamp(),
+	    Uuid(uuid) => uuid.hyphenated(),
 	    IFn(_) => std::string::String::from("#function[]"),
 	    LexicalEvalFn => std::string::String::from("#function[lexical-eval*]"),
 	    PersistentList(plist) => plist.to_string(),
 	    PersistentVector(pvector) => pvector.to_string(),
 	    PersistentListMap(plistmap) => plistmap.to_string(),
-	    Condition(msg) => format!("#Condition[\"{}\"]",msg),
+	    PersistentHashSet(phashset) => phashset.to_string(),
+	    Condition(condition) => condition.to_string(),
+	    HeldCondition(condition) => condition.to_string(),
+	    RestartInvocation(invocation) => format!("#RestartInvocation[{}]",invocation.name),
 	    Macro(_) => std::string::String::from("#macro[]"),
 	    QuoteMacro => std::string::String::from("#macro[quote*]"),
 	    DefMacro => std::string::String::from("#macro[def*]"),
 	    DefmacroMacro => std::string::String::from("#macro[defmacro*]"),
 	    FnMacro => std::string::String::from("#macro[fn*]"),
 	    LetMacro => std::string::String::from("#macro[let*]"),
+	    IfMacro => std::string::String::from("#macro[if*]"),
+	    DoMacro => std::string::String::from("#macro[do*]"),
+	    LoopMacro => std::string::String::from("#macro[loop*]"),
+	    RecurMacro => std::string::String::from("#macro[recur*]"),
+	    HandlerBindMacro => std::string::String::from("#macro[handler-bind*]"),
+	    RestartCaseMacro => std::string::String::from("#macro[restart-case*]"),
+	    TryMacro => std::string::String::from("#macro[try*]"),
+	    ThrowMacro => std::string::String::from("#macro[throw*]"),
 	    Value::String(string) => string.clone(),
+	    Boolean(b) => b.to_string(),
 	    Nil => std::string::String::from("nil"),
 	};
 	write!(f, "{}", str)
@@ -241,20 +463,106 @@ impl Value {
     //
     pub fn to_string_explicit(&self) -> std::string::String {
 	match self {
-	    Value::String(string) => format!("\"{}\"",string),
+	    // Escaped,  so we read back exactly the string we printed 
+	    Value::String(string) => {
+		let mut escaped = std::string::String::from("\"");
+		for ch in string.chars() {
+		    match ch {
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			'\t' => escaped.push_str("\\t"),
+			'\r' => escaped.push_str("\\r"),
+			'\u{8}' => escaped.push_str("\\b"),
+			'\u{c}' => escaped.push_str("\\f"),
+			ch => escaped.push(ch)
+		    }
+		}
+		escaped.push('"');
+		escaped
+	    },
+	    // \a,  \newline .. 
+	    Value::Char(ch) => match ch {
+		'\n' => std::string::String::from("\\newline"),
+		' ' => std::string::String::from("\\space"),
+		'\t' => std::string::String::from("\\tab"),
+		'\r' => std::string::String::from("\\return"),
+		'\u{8}' => std::string::String::from("\\backspace"),
+		'\u{c}' => std::string::String::from("\\formfeed"),
+		ch => format!("\\{}",ch)
+	    },
+	    // #inst "2020-01-01T00:00:00.000-00:00",  which reads back as the same instant 
+	    Value::Inst(inst) => inst.to_string(),
+	    Value::Uuid(uuid) => uuid.to_string(),
 	    _ => self.to_string()
 	}
     }
+    /// Clojure truthiness; only nil and false are falsey, everything else (0, "", (), ..) is truthy
+    pub fn is_truthy(&self) -> bool {
+	!matches!(self,Value::Nil | Value::Boolean(false))
+    }
+    /// Whether this is not a value to compute with at all,  but a transfer of control on its way
+    /// back up the stack,  which whatever is evaluating it should abandon its work and pass on 
+    pub fn is_unwinding(&self) -> bool {
+	matches!(self,Value::Condition(_) | Value::RestartInvocation(_))
+    }
+    /// This value as something we can invoke on already evaluated args,  if it's function-like;
+    /// a fn,  a keyword,  or a map,  vector or set.  Not a macro,  which wants its args as forms 
+    pub fn as_ifn(&self) -> Option<&dyn IFn> {
+	match self {
+	    Value::IFn(ifn) => Some(&**ifn),
+	    Value::Keyword(keyword) => Some(keyword),
+	    Value::PersistentListMap(plistmap) => Some(plistmap),
+	    Value::PersistentVector(pvector) => Some(pvector),
+	    Value::PersistentHashSet(phashset) => Some(phashset),
+	    _ => None
+	}
+    }
+    /// Our metadata,  if we're something that can carry it and have some 
+    pub fn meta(&self) -> Option<Rc<PersistentListMap>> {
+	match self {
+	    Value::Symbol(sym) => sym.meta(),
+	    Value::PersistentList(plist) => plist.meta(),
+	    Value::PersistentVector(pvector) => IMeta::meta(pvector),
+	    Value::PersistentListMap(plistmap) => IMeta::meta(plistmap),
+	    Value::PersistentHashSet(phashset) => IMeta::meta(phashset),
+	    Value::IFn(ifn) => ifn.meta(),
+	    _ => None
+	}
+    }
+    /// A copy of us with our metadata replaced by meta,  or None if we can't carry metadata 
+    pub fn with_meta(&self,meta: Option<Rc<PersistentListMap>>) -> Option<Value> {
+	match self {
+	    Value::Symbol(sym) => Some(Value::Symbol(sym.with_meta(meta))),
+	    Value::PersistentList(plist) => Some(Value::PersistentList(plist.with_meta(meta))),
+	    Value::PersistentVector(pvector) => Some(Value::PersistentVector(pvector.with_meta(meta))),
+	    Value::PersistentListMap(plistmap) => Some(Value::PersistentListMap(plistmap.with_meta(meta))),
+	    Value::PersistentHashSet(phashset) => Some(Value::PersistentHashSet(phashset.with_meta(meta))),
+	    Value::IFn(ifn) => Some(FnWithMeta::new(Rc::clone(ifn),meta).to_value()),
+	    _ => None
+	}
+    }
     pub fn type_tag(&self) -> TypeTag {
         match self {
             Value::I32(_) => TypeTag::I32,
+            Value::I64(_) => TypeTag::I64,
+            Value::BigInt(_) => TypeTag::BigInt,
+            Value::Ratio(_) => TypeTag::Ratio,
+            Value::F64(_) => TypeTag::F64,
             Value::Symbol(_) => TypeTag::Symbol,
+            Value::Keyword(_) => TypeTag::Keyword,
+            Value::Char(_) => TypeTag::Char,
+            Value::Inst(_) => TypeTag::Inst,
+            Value::Uuid(_) => TypeTag::Uuid,
             Value::IFn(_) => TypeTag::IFn,
 	    Value::LexicalEvalFn => TypeTag::IFn,
             Value::PersistentList(_) => TypeTag::PersistentList,
 	    Value::PersistentVector(_) => TypeTag::PersistentVector,
 	    Value::PersistentListMap(_) => TypeTag::PersistentListMap,
+	    Value::PersistentHashSet(_) => TypeTag::PersistentHashSet,
             Value::Condition(_) => TypeTag::Condition,
+	    Value::HeldCondition(_) => TypeTag::Condition,
+	    Value::RestartInvocation(_) => TypeTag::Condition,
             // Note; normal Clojure cannot take the value of a macro, so I don't imagine this
 	    // having significance in the long run, but we will see 
 	    Value::Macro(_) => TypeTag::Macro,
@@ -263,7 +571,16 @@ impl Value {
 	    Value::DefmacroMacro => TypeTag::Macro,
 	    Value::LetMacro => TypeTag::Macro,
 	    Value::FnMacro => TypeTag::Macro,
+	    Value::IfMacro => TypeTag::Macro,
+	    Value::DoMacro => TypeTag::Macro,
+	    Value::LoopMacro => TypeTag::Macro,
+	    Value::RecurMacro => TypeTag::Macro,
+	    Value::HandlerBindMacro => TypeTag::Macro,
+	    Value::RestartCaseMacro => TypeTag::Macro,
+	    Value::TryMacro => TypeTag::Macro,
+	    Value::ThrowMacro => TypeTag::Macro,
 	    Value::String(_) => TypeTag::String,
+	    Value::Boolean(_) => TypeTag::Boolean,
             Value::Nil => TypeTag::Nil 
 
         }
@@ -289,33 +606,44 @@ impl Value {
     // IFn application, it might as well be a list of functions itself.  It in fact means you don't have to
     // hunt around for each individual implementation.  
     //
+    /// Evaluates args,  and invokes ifn on them 
+    fn invoke_on_args(ifn: &dyn IFn,environment: &Rc<Environment>,args: &Rc<PersistentList>) -> Invocation {
+	// Eval arguments 
+	let evaled_arg_values = match Value::eval_each(PersistentList::iter(args),environment) {
+	    Ok(evaled_arg_values) => evaled_arg_values,
+	    Err(unwinding) => return Done(unwinding)
+	};
+	// Collect references for invoke 
+	let evaled_args_refs = evaled_arg_values.iter().map(|rc_arg| &**rc_arg).collect::<Vec<&Value>>();
+	// Invoke fn on arguments;  fns with a body left to evaluate hand it back to us
+	// as a tail call, rather than evaluating it themselves 
+	ifn.invoke_tail(evaled_args_refs)
+    }
     /// Applies any valid function-like Value to a PersistentList, or returns None if our Value can't be applied
-    fn apply_to_persistent_list(&self,environment: &Rc<Environment>,args: &Rc<PersistentList>) -> Option<Rc<Value>> {
+    ///
+    /// Anything left to evaluate in tail position (a fn's body, the branch an if picks, a macroexpansion ..)
+    /// is returned as a TailCall instead of being evaluated here, so our eval loop can continue with it
+    /// without growing the Rust stack 
+    fn apply_to_persistent_list(&self,environment: &Rc<Environment>,args: &Rc<PersistentList>) -> Option<Invocation> {
 	match self { 
-	     Value::IFn(ifn) => {
-		// Eval arguments 
-		let evaled_arg_values = PersistentList::iter(args).map(|rc_arg| {
-                    rc_arg.eval(Rc::clone(environment))
-		}).collect::<Vec<Value>>();
-		// Collect references for invoke 
-		let evaled_args_refs = evaled_arg_values.iter().map(|arg| {
-                    arg
-		}).collect::<Vec<&Value>>();
-		// Invoke fn on arguments 
-		Some(Rc::new(ifn.invoke(evaled_args_refs)))
-             },
+	     Value::IFn(ifn) => Some(Value::invoke_on_args(&**ifn,environment,args)),
+	    // (:name {:name "Blah"}) 
+	    Value::Keyword(keyword) => Some(Value::invoke_on_args(keyword,environment,args)),
+	    // ({:name "Blah"} :name),  ([:a :b :c] 1),  (#{:a :b} :a) 
+	    Value::PersistentListMap(plistmap) => Some(Value::invoke_on_args(plistmap,environment,args)),
+	    Value::PersistentVector(pvector) => Some(Value::invoke_on_args(pvector,environment,args)),
+	    Value::PersistentHashSet(phashset) => Some(Value::invoke_on_args(phashset,environment,args)),
 	    LexicalEvalFn => {
 		if args.len() != 1 {
-		    return Some(Rc::new(Value::Condition(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()))));
+		    return Some(Done(Rc::new(condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len())))));
 		}
 		// This should only be one value
-		let evaled_arg_values = PersistentList::iter(args).map(|rc_arg| {
-                    rc_arg.eval(Rc::clone(environment))
-		}).collect::<Vec<Value>>();
-
-		let evaled_arg = evaled_arg_values.get(0).unwrap();
+		let evaled_arg = args.nth(0).eval_to_rc(Rc::clone(environment));
+		if evaled_arg.is_unwinding() {
+		    return Some(Done(evaled_arg));
+		}
 		
-		Some(evaled_arg.eval_to_rc(Rc::clone(environment)))
+		Some(TailCall(evaled_arg,Rc::clone(environment)))
 	    },
 	    //
 	    // Unless I'm mistaken, this is incorrect; instead of having a phase where
@@ -335,13 +663,11 @@ impl Value {
                 }).collect::<Vec<Value>>();
 
                 
-                let arg_refs =  arg_values.iter().map(|arg| {
-                    arg
-                }).collect::<Vec<&Value>>();
+                let arg_refs =  arg_values.iter().collect::<Vec<&Value>>();
 
                 let macroexpansion = Rc::new(ifn.invoke(arg_refs));
 
-		Some(macroexpansion.eval_to_rc(Rc::clone(environment)))
+		Some(TailCall(macroexpansion,Rc::clone(environment)))
 		    
             },
 	    //
@@ -364,36 +690,54 @@ impl Value {
 	    //   as an implementation of the generic Value::Macro(Rc<IFn>) 
 	    //
 	    DefMacro => {
-		let arg_rc_values = PersistentList::iter(args).map(|rc_arg| {
-		    rc_arg
-                }).collect::<Vec<Rc<Value>>>();
+		let arg_rc_values = PersistentList::iter(args).collect::<Vec<Rc<Value>>>();
 		
-		if arg_rc_values.len() > 2 || arg_rc_values.is_empty()  {
-		    return Some(Rc::new(Value::Condition(format!("Wrong number of arguments (Given: {}, Expected: 1-2)",arg_rc_values.len()))));
+		if arg_rc_values.len() > 3 || arg_rc_values.is_empty()  {
+		    return Some(Done(Rc::new(condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1-3)",arg_rc_values.len())))));
+		}
+		let defname = arg_rc_values.first().unwrap();
+		// (def name "docstring" val) 
+		let (docstring,defform) = match arg_rc_values.len() {
+		    3 => match &*arg_rc_values[1] {
+			Value::String(_) => (Some(Rc::clone(&arg_rc_values[1])),arg_rc_values.get(2)),
+			_ => return Some(Done(Rc::new(condition::raise(std::string::String::from("Too many arguments to def")))))
+		    },
+		    _ => (None,arg_rc_values.get(1))
+		};
+		let defval = match defform {
+		    Some(defform) => defform.eval_to_rc(Rc::clone(environment)),
+		    None => Rc::new(Value::Nil)
+		};
+		if defval.is_unwinding() {
+		    return Some(Done(defval));
 		}
-		let defname = arg_rc_values.get(0).unwrap();
-		let defval = arg_rc_values.get(1).unwrap().eval_to_rc(Rc::clone(&environment));
-		// Let's not do docstrings yet 
-		// let docstring = ...
 		match &**defname {
 		    Value::Symbol(sym) => {
+			// Until we have vars to hold it,  the metadata of our name (and our docstring)
+			// goes on the value we're naming,  if it can carry any 
+			let mut name_meta = sym.meta().map_or(PersistentListMap::Empty(None),|meta| (*meta).clone());
+			if let Some(docstring) = docstring {
+			    name_meta = name_meta.assoc(Keyword::intern("doc").to_rc_value(),docstring);
+			}
+			let defval = match name_meta {
+			    PersistentListMap::Empty(_) => defval,
+			    name_meta => defval.with_meta(Some(meta::merge_meta(defval.meta(),&name_meta))).map_or(defval,Rc::new)
+			};
 			environment.insert(sym.clone(),defval);
 			// @TODO return var. For now, however, we only have symbols
 			// @TODO intern from environment, don't make new sym ?
-			Some(sym.to_rc_value())
+			Some(Done(sym.to_rc_value()))
 		    },
-		    _ => Some(Rc::new(Value::Condition(std::string::String::from("First argument to def must be a symbol"))))
+		    _ => Some(Done(Rc::new(condition::raise(std::string::String::from("First argument to def must be a symbol")))))
 		}
 	    },
 	    DefmacroMacro => {
-		let arg_rc_values = PersistentList::iter(args).map(|rc_arg| {
-		    rc_arg
-                }).collect::<Vec<Rc<Value>>>();
+		let arg_rc_values = PersistentList::iter(args).collect::<Vec<Rc<Value>>>();
 		
 		if arg_rc_values.len() < 2 || arg_rc_values.is_empty() {
-		    return Some(Rc::new(Value::Condition(format!("Wrong number of arguments (Given: {}, Expected: >=2)",args.len()))))
+		    return Some(Done(Rc::new(condition::raise(format!("Wrong number of arguments (Given: {}, Expected: >=2)",args.len())))))
 		}
-		let macro_name = arg_rc_values.get(0).unwrap();
+		let macro_name = arg_rc_values.first().unwrap();
 		let macro_args = arg_rc_values.get(1).unwrap();
 
 		let macro_body_exprs =
@@ -408,16 +752,16 @@ impl Value {
 		];
 		// vec![do expr1 expr2 expr3]
 		macro_invokable_body_vec.extend_from_slice(macro_body_exprs);
-		let macro_invokable_body = macro_invokable_body_vec.into_list().eval(Rc::clone(&environment));
+		let macro_invokable_body = macro_invokable_body_vec.into_list().eval(Rc::clone(environment));
 		let macro_value = match &macro_invokable_body {
-		    Value::IFn(ifn) => Rc::new(Value::Macro(Rc::clone(&ifn))),
-		    _ => Rc::new(Value::Condition(std::string::String::from("Compiler Error: your macro_value somehow compiled into something else entirely.  I don't even know how that happened,  this behavior is hardcoded, that's impressive")))
+		    Value::IFn(ifn) => Rc::new(Value::Macro(Rc::clone(ifn))),
+		    _ => Rc::new(condition::raise(std::string::String::from("Compiler Error: your macro_value somehow compiled into something else entirely.  I don't even know how that happened,  this behavior is hardcoded, that's impressive")))
 		};
-		Some(vec![
+		Some(TailCall(vec![
 		    Symbol::intern("def").to_rc_value(),
 		    Rc::clone(macro_name),
 		    macro_value
-		].into_list().eval_to_rc(Rc::clone(&environment)))
+		].into_list().to_rc_value(),Rc::clone(environment)))
 	    },
 	    //
 	    // (fn [x y z] (+ x y z)) 
@@ -425,76 +769,61 @@ impl Value {
 	    // @TODO Rename for* everywhere, define for in terms of for* in
 	    //       ClojureRS
 	    FnMacro => {
-		let arg_rc_values = PersistentList::iter(args).map(|rc_arg| {
-		    rc_arg
-                }).collect::<Vec<Rc<Value>>>();
+		let arg_rc_values = PersistentList::iter(args).collect::<Vec<Rc<Value>>>();
 		
-		if arg_rc_values.len() < 1 {
-		    return Some(Rc::new(Value::Condition(format!("Wrong number of arguments (Given: {}, Expect: >=1",arg_rc_values.len()))));
+		if arg_rc_values.is_empty() {
+		    return Some(Done(Rc::new(condition::raise(format!("Wrong number of arguments (Given: {}, Expect: >=1",arg_rc_values.len())))));
 		}
 		// Let's not do fn names yet 
-		// let fnname = arg_rc_values.get(0).unwrap();
-		let fn_args = arg_rc_values.get(0).unwrap();
+		// let fnname = arg_rc_values.first().unwrap();
+		let fn_args = arg_rc_values.first().unwrap();
 		// Let's not do docstrings yet 
 		// let docstring = ...
 		match &**fn_args {
-		    Value::PersistentVector(PersistentVector{vals}) => {
+		    Value::PersistentVector(PersistentVector{vals,..}) => {
 			let mut arg_syms_vec = vec![];
 			let enclosing_environment =
-			    Rc::new(Environment::new_local_environment(Rc::clone(&environment)));
+			    Rc::new(Environment::new_local_environment(Rc::clone(environment)));
 			for val in vals.iter() {
 			    if let Value::Symbol(sym) = &**val {
 				arg_syms_vec.push(sym.clone());
 			    }
 			}
 			
-			let fn_body =
-			// (fn [x y] ) -> nil 
-			    if arg_rc_values.len() <= 1 {
-				Rc::new(Value::Nil)
-				// (fn [x y] expr) -> expr 
-			    } else if arg_rc_values.len() == 2 {
-				Rc::clone(arg_rc_values.get(1).unwrap())
-				// (fn [x y] expr1 expr2 expr3) -> (do expr1 expr2 expr3) 
-			    } else {
-				// (&[expr1 expr2 expr3] 
-				let body_exprs = arg_rc_values.get(1..).unwrap();
-				// vec![do]
-				let mut do_body = vec![Symbol::intern("do").to_rc_value()];
-				// vec![do expr1 expr2 expr3]
-				do_body.extend_from_slice(body_exprs);
-				// (do expr1 expr2 expr3) 
-				do_body.into_list().to_rc_value()
-			    };
+			let fn_body = match Value::checked_body(Value::body_from_exprs(arg_rc_values.get(1..).unwrap()),args,environment) {
+			    Ok(fn_body) => fn_body,
+			    Err(condition) => return Some(Done(Rc::new(condition)))
+			};
 			
-			Some(Rc::new(lambda::Fn{
-			    body: fn_body,
+			Some(Done(Rc::new(lambda::Fn::new(
+			    fn_body,
 			    enclosing_environment,
-			    arg_syms: arg_syms_vec
-			}.to_value()))
+			    arg_syms_vec
+			).to_value())))
 		    },
-		    _ => Some(Rc::new(Value::Condition(std::string::String::from("First argument to def must be a symbol"))))
+		    _ => Some(Done(Rc::new(condition::raise(std::string::String::from("First argument to def must be a symbol")))))
 		}
 	    },
 	    LetMacro => {
-		let arg_rc_values = PersistentList::iter(args).map(|rc_arg| {
-		    rc_arg
-                }).collect::<Vec<Rc<Value>>>();
-		if arg_rc_values.len() < 1 || arg_rc_values.len() > 2 {
-		    return Some(Rc::new(Value::Condition(std::string::String::from("Wrong number of arguments given to let (Given: 0, Expecting: 1 or 2)"))));
+		let arg_rc_values = PersistentList::iter(args).collect::<Vec<Rc<Value>>>();
+		if arg_rc_values.is_empty() || arg_rc_values.len() > 2 {
+		    return Some(Done(Rc::new(condition::raise(std::string::String::from("Wrong number of arguments given to let (Given: 0, Expecting: 1 or 2)")))));
 		}
 		// Already guaranteed to exist by earlier checks 
-		let local_bindings = arg_rc_values.get(0).unwrap();
+		let local_bindings = arg_rc_values.first().unwrap();
 		match &**local_bindings {
 		    Value::PersistentVector(vector) => {
 			//let mut local_environment_map : HashMap<Symbol,Rc<Value>> = HashMap::new();
 			let local_environment = Rc::new(Environment::new_local_environment(Rc::clone(environment)));
 			// let chunk_test2 = 
 			for pair in vector.vals.chunks(2) {
-			    if let Some(rc_sym) = (&*pair).get(0) //(*pair[0]).clone()
+			    if let Some(rc_sym) = pair.first() //(*pair[0]).clone()
 			    {
 				let val =
-				    (&*pair).get(1).unwrap().eval_to_rc(Rc::clone(&local_environment));
+				    pair.get(1).unwrap().eval_to_rc(Rc::clone(&local_environment));
+				if val.is_unwinding() {
+				    return Some(Done(val));
+				}
 				if let Value::Symbol(sym) = &(**rc_sym) {
 				    local_environment.insert(sym.clone(),val);
 				    //println!("Sym found: {:?}: {:?}",sym,val)
@@ -506,29 +835,324 @@ impl Value {
 			}
 			let body = arg_rc_values.get(1);
 			if let Some(body_) = body {
-			    Some(body_.eval_to_rc(local_environment))
+			    Some(TailCall(Rc::clone(body_),local_environment))
 			}
 			else {
-			    Some(Rc::new(Value::Nil))
+			    Some(Done(Rc::new(Value::Nil)))
 			}
 		    },
-		    _ => Some(Rc::new(Value::Condition(std::string::String::from("Bindings to let should be a vector"))))
+		    _ => Some(Done(Rc::new(condition::raise(std::string::String::from("Bindings to let should be a vector")))))
 		} 
 	    },
+	    //
+	    // (if test then else?)
+	    //
+	    // Must be special; a fn would evaluate both branches before we got to choose one
+	    //
+	    IfMacro => {
+		if args.len() < 2 || args.len() > 3 {
+		    return Some(Done(Rc::new(condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 2-3)",args.len())))));
+		}
+		let test = args.nth(0).eval_to_rc(Rc::clone(environment));
+		if test.is_unwinding() {
+		    return Some(Done(test));
+		}
+		if test.is_truthy() {
+		    Some(TailCall(args.nth(1),Rc::clone(environment)))
+		}
+		else {
+		    // (if false 1) => nil;  nth already gives us nil for a missing else branch 
+		    Some(TailCall(args.nth(2),Rc::clone(environment)))
+		}
+	    },
+	    //
+	    // (loop [x 1 y 2] expr1 expr2 expr3)
+	    //
+	    // Binds like let,  but also sets up a RecursionPoint our body can recur back to 
+	    //
+	    LoopMacro => {
+		let arg_rc_values = PersistentList::iter(args).collect::<Vec<Rc<Value>>>();
+		if arg_rc_values.is_empty() {
+		    return Some(Done(Rc::new(condition::raise(std::string::String::from("Wrong number of arguments given to loop (Given: 0, Expected: >=1)")))));
+		}
+		let loop_body = match Value::checked_body(Value::body_from_exprs(arg_rc_values.get(1..).unwrap()),args,environment) {
+		    Ok(loop_body) => loop_body,
+		    Err(condition) => return Some(Done(Rc::new(condition)))
+		};
+		match &**arg_rc_values.first().unwrap() {
+		    Value::PersistentVector(vector) => {
+			if vector.vals.len() % 2 != 0 {
+			    return Some(Done(Rc::new(condition::raise(std::string::String::from("loop requires an even number of forms in its binding vector")))));
+			}
+			let local_environment = Rc::new(Environment::new_local_environment(Rc::clone(environment)));
+			let mut params = vec![];
+			for pair in vector.vals.chunks(2) {
+			    match &*pair[0] {
+				Value::Symbol(sym) => {
+				    let val = pair[1].eval_to_rc(Rc::clone(&local_environment));
+				    if val.is_unwinding() {
+					return Some(Done(val));
+				    }
+				    local_environment.insert(sym.clone(),val);
+				    params.push(sym.clone());
+				},
+				_ => {
+				    return Some(Done(Rc::new(condition::raise(format!("Bad binding form in loop, expected symbol, got: {}",pair[0])))));
+				}
+			    }
+			}
+			Some(Enter(Rc::new(RecursionPoint {
+			    params,
+			    body: loop_body,
+			    environment: Rc::clone(environment)
+			}),local_environment))
+		    },
+		    _ => Some(Done(Rc::new(condition::raise(std::string::String::from("Bindings to loop should be a vector")))))
+		}
+	    },
+	    //
+	    // (recur expr1 expr2)
+	    //
+	    // Evaluates its arguments,  and then leaves it to our eval loop to jump back to the
+	    // enclosing fn or loop with them 
+	    //
+	    RecurMacro => {
+		match Value::eval_each(PersistentList::iter(args),environment) {
+		    Ok(evaled_args) => Some(Recur(evaled_args)),
+		    Err(unwinding) => Some(Done(unwinding))
+		}
+	    },
+	    //
+	    // (do expr1 expr2 expr3)
+	    //
+	    // Used to expand into a plain fn that just evaluated all of its arguments,  but as
+	    // a special form our last expression is left in tail position, where it belongs
+	    //
+	    DoMacro => {
+		let arg_rc_values = PersistentList::iter(args).collect::<Vec<Rc<Value>>>();
+		match arg_rc_values.split_last() {
+		    Some((last_expr,exprs)) => {
+			for expr in exprs.iter() {
+			    let value = expr.eval_to_rc(Rc::clone(environment));
+			    if value.is_unwinding() {
+				return Some(Done(value));
+			    }
+			}
+			Some(TailCall(Rc::clone(last_expr),Rc::clone(environment)))
+		    },
+		    // (do) => nil 
+		    None => Some(Done(Rc::new(Value::Nil)))
+		}
+	    },
+	    //
+	    // (handler-bind type1 handler1 type2 handler2 expr)
+	    //
+	    // Evaluates expr with our handlers established (see condition.rs);  our types are
+	    // keywords,  matched against the type given to error 
+	    //
+	    HandlerBindMacro => {
+		let arg_rc_values = PersistentList::iter(args).collect::<Vec<Rc<Value>>>();
+		if arg_rc_values.len() % 2 == 0 {
+		    return Some(Done(Rc::new(condition::raise(format!("Wrong number of arguments given to handler-bind (Given: {}, Expected: type and handler pairs followed by an expression)",arg_rc_values.len())))));
+		}
+		let (expr,handler_pairs) = arg_rc_values.split_last().unwrap();
+		let mut handlers = vec![];
+		for pair in handler_pairs.chunks(2) {
+		    if let Value::Keyword(_) = &*pair[0] {} else {
+			return Some(Done(Rc::new(condition::raise(format!("Handler type must be a keyword, got: {}",pair[0])))));
+		    }
+		    let handler_fn = pair[1].eval_to_rc(Rc::clone(environment));
+		    match &*handler_fn {
+			Value::IFn(ifn) => handlers.push(Handler {
+			    condition_type: Rc::clone(&pair[0]),
+			    handler_fn: Some(Rc::clone(ifn))
+			}),
+			_ if handler_fn.is_unwinding() => return Some(Done(handler_fn)),
+			_ => {
+			    return Some(Done(Rc::new(condition::raise(format!("Handler for {} must be a fn, got: {}",pair[0],handler_fn.type_tag())))));
+			}
+		    }
+		}
+		// Our handlers only last as long as expr,  so it can't be left to our eval loop
+		// as a tail call 
+		let depth = condition::push_handlers(handlers);
+		let result = expr.eval_to_rc(Rc::clone(environment));
+		condition::pop_handlers(depth);
+		Some(Done(result))
+	    },
+	    //
+	    // (restart-case expr
+	    //   (restart-name [x y] body1 body2)
+	    //   (other-restart-name [] body))
+	    //
+	    // Evaluates expr with our restarts established;  if a handler invokes one of them
+	    // while we're evaluating,  we end up back here,  and evaluate that restart's body instead 
+	    //
+	    RestartCaseMacro => {
+		let arg_rc_values = PersistentList::iter(args).collect::<Vec<Rc<Value>>>();
+		let (expr,clauses) = match arg_rc_values.split_first() {
+		    Some(expr_and_clauses) => expr_and_clauses,
+		    None => {
+			return Some(Done(Rc::new(condition::raise(std::string::String::from("Wrong number of arguments given to restart-case (Given: 0, Expected: >=1)")))));
+		    }
+		};
+		let restart_case_id = condition::new_restart_case_id();
+		// (restart, body)
+		let mut restart_clauses = vec![];
+		for clause in clauses.iter() {
+		    let clause_forms = match &**clause {
+			Value::PersistentList(plist) => PersistentList::iter(&Rc::new(plist.clone())).collect::<Vec<Rc<Value>>>(),
+			_ => vec![]
+		    };
+		    match (clause_forms.first().map(|name| &**name),clause_forms.get(1).map(|params| &**params)) {
+			(Some(Value::Symbol(name)),Some(Value::PersistentVector(params))) => {
+			    let mut param_syms = vec![];
+			    for param in params.vals.iter() {
+				match &**param {
+				    Value::Symbol(sym) => param_syms.push(sym.clone()),
+				    _ => {
+					return Some(Done(Rc::new(condition::raise(format!("Bad parameter for restart {}, expected symbol, got: {}",name,param)))));
+				    }
+				}
+			    }
+			    restart_clauses.push((
+				Restart {
+				    restart_case_id,
+				    name: name.name.clone(),
+				    params: param_syms
+				},
+				Value::body_from_exprs(clause_forms.get(2..).unwrap())
+			    ));
+			},
+			_ => {
+			    return Some(Done(Rc::new(condition::raise(format!("Bad restart clause {}, expected (name [params] body)",clause)))));
+			}
+		    }
+		}
+
+		let restarts = restart_clauses.iter().map(|(restart,_)| restart.clone()).collect::<Vec<Restart>>();
+		let depth = condition::push_restarts(restarts);
+		let result = expr.eval_to_rc(Rc::clone(environment));
+		condition::pop_restarts(depth);
+
+		match &*result {
+		    Value::RestartInvocation(invocation) if invocation.restart_case_id == restart_case_id => {
+			// Only restarts we've established are ever given our id 
+			let (restart,body) = restart_clauses.iter().find(|(restart,_)| restart.name == invocation.name).unwrap();
+			if invocation.args.len() != restart.params.len() {
+			    return Some(Done(Rc::new(condition::raise(format!("Wrong number of arguments given to restart {} (Given: {}, Expected: {})",restart.name,invocation.args.len(),restart.params.len())))));
+			}
+			let local_environment = Rc::new(Environment::new_local_environment(Rc::clone(environment)));
+			for (param,arg) in restart.params.iter().zip(invocation.args.iter()) {
+			    local_environment.insert(param.clone(),Rc::clone(arg));
+			}
+			Some(TailCall(Rc::clone(body),local_environment))
+		    },
+		    _ => Some(Done(result))
+		}
+	    },
+	    //
+	    // (try expr1 expr2
+	    //   (catch type e body1 body2)
+	    //   (finally body1 body2))
+	    //
+	    // For the Clojure code that expects it;  unlike a handler,  a catch only runs once
+	    // we've already unwound back out to it.  While our body runs,  each catch is
+	    // established as a handler too,  so what it catches unwinds straight to us rather
+	    // than on to the handlers and debugger hook further out 
+	    //
+	    TryMacro => {
+		let arg_rc_values = PersistentList::iter(args).collect::<Vec<Rc<Value>>>();
+		// Everything up to our first catch or finally is our body 
+		let clause_start = arg_rc_values.iter().position(|arg| {
+		    Value::special_clause_name(arg).is_some_and(|name| name == "catch" || name == "finally")
+		}).unwrap_or(arg_rc_values.len());
+		let body = Value::body_from_exprs(&arg_rc_values[..clause_start]);
+		// (type, sym, body) 
+		let mut catch_clauses = vec![];
+		let mut finally_body = None;
+		for (ind,clause) in arg_rc_values[clause_start..].iter().enumerate() {
+		    let clause_forms = match &**clause {
+			Value::PersistentList(plist) => PersistentList::iter(&Rc::new(plist.clone())).collect::<Vec<Rc<Value>>>(),
+			_ => vec![]
+		    };
+		    match Value::special_clause_name(clause).as_deref() {
+			Some("catch") if finally_body.is_none() => {
+			    match (clause_forms.get(1),clause_forms.get(2).map(|sym| &**sym)) {
+				(Some(catch_type),Some(Value::Symbol(sym))) => catch_clauses.push((
+				    Rc::clone(catch_type),
+				    sym.clone(),
+				    Value::body_from_exprs(clause_forms.get(3..).unwrap())
+				)),
+				_ => {
+				    return Some(Done(Rc::new(condition::raise(format!("Bad catch clause {}, expected (catch type name body)",clause)))));
+				}
+			    }
+			},
+			Some("finally") if ind == arg_rc_values.len() - clause_start - 1 => {
+			    finally_body = Some(Value::body_from_exprs(clause_forms.get(1..).unwrap()));
+			},
+			_ => {
+			    return Some(Done(Rc::new(condition::raise(format!("Bad clause in try {}; only catch clauses, and then one finally, may follow its body",clause)))));
+			}
+		    }
+		}
+
+		let catch_handlers = catch_clauses.iter().map(|(catch_type,_,_)| Handler {
+		    condition_type: Rc::clone(catch_type),
+		    handler_fn: None
+		}).collect::<Vec<Handler>>();
+		let depth = condition::push_handlers(catch_handlers);
+		let mut result = body.eval_to_rc(Rc::clone(environment));
+		condition::pop_handlers(depth);
+		if let Value::Condition(condition) = &*result {
+		    let catch_clause = catch_clauses.iter().find(|(catch_type,_,_)| condition.is_caught_by(catch_type));
+		    if let Some((_,sym,catch_body)) = catch_clause {
+			let local_environment = Rc::new(Environment::new_local_environment(Rc::clone(environment)));
+			// Held, with its trace intact,  so (throw e) picks up right where it left off 
+			local_environment.insert(sym.clone(),Rc::new(Value::HeldCondition(condition.clone())));
+			result = catch_body.eval_to_rc(local_environment);
+		    }
+		}
+		// Whatever happened above,  even a restart unwinding through us 
+		if let Some(finally_body) = finally_body {
+		    let finally_result = finally_body.eval_to_rc(Rc::clone(environment));
+		    if finally_result.is_unwinding() {
+			return Some(Done(finally_result));
+		    }
+		}
+		Some(Done(result))
+	    },
+	    //
+	    // (throw condition)
+	    //
+	    // Sets a condition held as a value (say,  made with ex-info,  or bound by catch) unwinding 
+	    //
+	    ThrowMacro => {
+		if args.len() != 1 {
+		    return Some(Done(Rc::new(condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len())))));
+		}
+		let thrown = args.nth(0).eval_to_rc(Rc::clone(environment));
+		match &*thrown {
+		    Value::HeldCondition(condition) => Some(Done(Rc::new(condition::signal(condition.clone())))),
+		    _ if thrown.is_unwinding() => Some(Done(thrown)),
+		    _ => Some(Done(Rc::new(condition::raise(format!("Type mismatch; Can only throw clojure.lang.Condition, Recieved type {}",thrown.type_tag())))))
+		}
+	    },
 	    // 
 	    // Quote is simply a primitive, a macro base case; trying to define quote without
 	    // quote just involves an infinite loop of macroexpansion. Or so it seems 
 	    // 
 	    QuoteMacro => {
 		if args.len() > 1 {
-		    Some(Rc::new(Value::Condition(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len()))))
+		    Some(Done(Rc::new(condition::raise(format!("Wrong number of arguments (Given: {}, Expected: 1)",args.len())))))
 		}
 		// @TODO define is_empty()
 		else if args.len() < 1 {
-		    Some(Rc::new(Value::Condition(std::string::String::from("Wrong number of arguments (Given: 0, Expected: 1)"))))
+		    Some(Done(Rc::new(condition::raise(std::string::String::from("Wrong number of arguments (Given: 0, Expected: 1)")))))
 		}
 		else {
-		    Some(args.nth(0))
+		    Some(Done(args.nth(0)))
 		}
 	    },
 	    //
@@ -537,6 +1161,171 @@ impl Value {
 	    _ => None 
 	}
     }
+    /// Evaluates each form in turn,  stopping short with the first value that is unwinding 
+    fn eval_each<I: Iterator<Item=Rc<Value>>>(forms: I,environment: &Rc<Environment>) -> Result<Vec<Rc<Value>>,Rc<Value>> {
+	let mut evaled_forms = vec![];
+	for form in forms {
+	    let evaled_form = form.eval_to_rc(Rc::clone(environment));
+	    if evaled_form.is_unwinding() {
+		return Err(evaled_form);
+	    }
+	    evaled_forms.push(evaled_form);
+	}
+	Ok(evaled_forms)
+    }
+    /// The name of a clause like (catch ..) or (finally ..),  or None if form isn't one
+    fn special_clause_name(form: &Rc<Value>) -> Option<std::string::String> {
+	match &**form {
+	    Value::PersistentList(Cons(head,..)) => match &**head {
+		Value::Symbol(sym) => Some(sym.name.clone()),
+		_ => None
+	    },
+	    _ => None
+	}
+    }
+    /// Turns the body expressions of something like fn or loop into the single form we evaluate
+    ///   []                  => nil
+    ///   [expr]              => expr
+    ///   [expr1 expr2 expr3] => (do expr1 expr2 expr3)
+    fn body_from_exprs(body_exprs: &[Rc<Value>]) -> Rc<Value> {
+	match body_exprs.len() {
+	    0 => Rc::new(Value::Nil),
+	    1 => Rc::clone(&body_exprs[0]),
+	    _ => {
+		let mut do_body = vec![Symbol::intern("do").to_rc_value()];
+		do_body.extend_from_slice(body_exprs);
+		do_body.into_list().to_rc_value()
+	    }
+	}
+    }
+    //
+    // Our stand-in for the compiler's check;  since a recur outside of tail position would
+    // have to return to where it was called, it can't simply jump back to its fn or loop.
+    // A fn or loop a macro expands to is checked when that expansion is evaluated;  macro
+    // calls inside our form are left alone,  and a recur they misplace is caught when it runs.
+    //
+    // A fn or loop is checked once,  when it's first built;  the fns and loops inside it are
+    // checked along with it,  and marked as such,  so building them again each time it runs
+    // doesn't check them all over again 
+    //
+    /// Returns a Condition describing the first recur in form that is not in tail position,
+    /// where in_tail is whether form itself is in tail position of its fn or loop;  or else
+    /// form,  with each fn and loop in it marked as checked (see is_recur_checked) 
+    fn check_recur_in_tail_position(form: &Rc<Value>,
+				    parent: Option<&Rc<Value>>,
+				    environment: &Rc<Environment>,
+				    in_tail: bool) -> Result<Rc<Value>,Value> {
+	// Checks each form, none of which are in tail position 
+	let check_all_non_tail = |forms: &[Rc<Value>]| -> Result<Vec<Rc<Value>>,Value> {
+	    forms.iter().map(|sub_form| Value::check_recur_in_tail_position(sub_form,Some(form),environment,false)).collect()
+	};
+	// Checks the last form in tail position (of whatever in_tail_for_last says),  the rest not 
+	let check_body = |forms: &[Rc<Value>],in_tail_for_last: bool| -> Result<Vec<Rc<Value>>,Value> {
+	    match forms.split_last() {
+		Some((last_form,rest)) => {
+		    let mut checked_forms = check_all_non_tail(rest)?;
+		    checked_forms.push(Value::check_recur_in_tail_position(last_form,Some(form),environment,in_tail_for_last)?);
+		    Ok(checked_forms)
+		},
+		None => Ok(vec![])
+	    }
+	};
+	match &**form {
+	    Value::PersistentList(Cons(head,tail,count,meta)) => {
+		let args = PersistentList::iter(tail).collect::<Vec<Rc<Value>>>();
+		let head_value = match &**head {
+		    Value::Symbol(sym) => environment.try_get(sym).unwrap_or_else(|| Rc::clone(head)),
+		    _ => Rc::clone(head)
+		};
+		// Our form again,  with its head and args checked 
+		let checked_form = |checked_head: Rc<Value>,checked_args: Vec<Rc<Value>>| {
+		    let mut checked_tail = checked_args.into_list();
+		    if let FnMacro | LoopMacro = &*head_value {
+			checked_tail = checked_tail.with_meta(Some(Rc::new(vec![MapEntry {
+			    key: Keyword::intern("recur-checked").to_rc_value(),
+			    val: Value::Boolean(true).to_rc_value()
+			}].into_list_map())));
+		    }
+		    Ok(Value::PersistentList(Cons(checked_head,Rc::new(checked_tail),*count,meta.clone())).to_rc_value())
+		};
+		match &*head_value {
+		    QuoteMacro | DefmacroMacro => Ok(Rc::clone(form)),
+		    RecurMacro => {
+			if !in_tail {
+			    return Err(condition::raise(match parent {
+				Some(parent) => format!("Can only recur from tail position; found {} in non-tail position of {}",form,parent),
+				None => format!("Can only recur from tail position; found {}",form)
+			    }));
+			}
+			checked_form(Rc::clone(head),check_all_non_tail(&args)?)
+		    },
+		    // (if test then else) 
+		    IfMacro => {
+			let mut checked_args = check_all_non_tail(args.get(..1).unwrap_or(&[]))?;
+			for branch in args.iter().skip(1) {
+			    checked_args.push(Value::check_recur_in_tail_position(branch,Some(form),environment,in_tail)?);
+			}
+			checked_form(Rc::clone(head),checked_args)
+		    },
+		    DoMacro => checked_form(Rc::clone(head),check_body(&args,in_tail)?),
+		    // (let [x 1 y 2] body)
+		    LetMacro | LoopMacro => {
+			let bindings = match args.first().map(|bindings| &**bindings) {
+			    Some(Value::PersistentVector(bindings)) => {
+				PersistentVector { vals: check_all_non_tail(&bindings.vals)?, meta: bindings.meta.clone() }.to_rc_value()
+			    },
+			    Some(_) => Rc::clone(&args[0]),
+			    None => return Ok(Rc::clone(form))
+			};
+			// A loop body is the tail of its own recursion point,  wherever the loop itself is 
+			let in_tail_for_body = if let LoopMacro = &*head_value { true } else { in_tail };
+			let mut checked_args = vec![bindings];
+			checked_args.extend(check_body(&args[1..],in_tail_for_body)?);
+			checked_form(Rc::clone(head),checked_args)
+		    },
+		    // (fn [x y] body);  also its own recursion point 
+		    FnMacro => {
+			let mut checked_args = args.get(..1).unwrap_or(&[]).to_vec();
+			checked_args.extend(check_body(args.get(1..).unwrap_or(&[]),true)?);
+			checked_form(Rc::clone(head),checked_args)
+		    },
+		    // We don't expand macros just to look inside them;  expanding one can have side
+		    // effects,  or fail.  A recur one puts out of tail position is caught as it runs 
+		    Macro(_) => Ok(Rc::clone(form)),
+		    // Otherwise, a plain function call 
+		    _ => {
+			let checked_head = Value::check_recur_in_tail_position(head,Some(form),environment,false)?;
+			checked_form(checked_head,check_all_non_tail(&args)?)
+		    }
+		}
+	    },
+	    Value::PersistentVector(pvector) => {
+		Ok(PersistentVector { vals: check_all_non_tail(&pvector.vals)?, meta: pvector.meta.clone() }.to_rc_value())
+	    },
+	    Value::PersistentListMap(plistmap) => {
+		let mut checked_entries = vec![];
+		for map_entry in plistmap.iter() {
+		    let checked_entry = check_all_non_tail(&[map_entry.key,map_entry.val])?;
+		    checked_entries.push(MapEntry { key: Rc::clone(&checked_entry[0]), val: Rc::clone(&checked_entry[1]) });
+		}
+		Ok(Rc::new(Value::PersistentListMap(checked_entries.into_iter().collect::<PersistentListMap>().with_meta(IMeta::meta(plistmap)))))
+	    },
+	    _ => Ok(Rc::clone(form))
+	}
+    }
+    /// Whether the args of a fn or loop were already checked for recurs out of tail position,
+    /// along with the fn or loop they're in 
+    fn is_recur_checked(args: &PersistentList) -> bool {
+	args.meta().is_some_and(|meta| meta.get(&Keyword::intern("recur-checked").to_rc_value()).is_truthy())
+    }
+    /// body,  checked for recurs out of tail position unless the args of the fn or loop it's
+    /// the body of already were 
+    fn checked_body(body: Rc<Value>,args: &PersistentList,environment: &Rc<Environment>) -> Result<Rc<Value>,Value> {
+	if Value::is_recur_checked(args) {
+	    return Ok(body);
+	}
+	Value::check_recur_in_tail_position(&body,None,environment,true)
+    }
     ////////////////////////////////////////////////////////////////////////////////////////////////////
     // Eval Helper
     ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -563,6 +1352,36 @@ impl ToValue for i32 {
         Value::I32(*self) 
     }
 }
+impl ToValue for i64 {
+    fn to_value(&self) -> Value {
+        Value::I64(*self) 
+    }
+}
+impl ToValue for f64 {
+    fn to_value(&self) -> Value {
+        Value::F64(*self) 
+    }
+}
+impl ToValue for char {
+    fn to_value(&self) -> Value {
+        Value::Char(*self)
+    }
+}
+impl ToValue for inst::Inst {
+    fn to_value(&self) -> Value {
+        Value::Inst(*self)
+    }
+}
+impl ToValue for uuid::Uuid {
+    fn to_value(&self) -> Value {
+        Value::Uuid(*self)
+    }
+}
+impl ToValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Boolean(*self)
+    }
+}
 impl ToValue for std::string::String {
     fn to_value(&self) -> Value {
         Value::String(self.clone()) 
@@ -578,6 +1397,11 @@ impl ToValue for Symbol {
         Value::Symbol(self.clone())
     }
 }
+impl ToValue for Keyword {
+    fn to_value(&self) -> Value {
+        Value::Keyword(self.clone())
+    }
+}
 impl ToValue for Rc<dyn IFn> {
     fn to_value(&self) -> Value {
         Value::IFn(Rc::clone(self))
@@ -598,6 +1422,11 @@ impl ToValue for PersistentListMap {
         Value::PersistentListMap(self.clone())
     }
 }
+impl ToValue for PersistentHashSet {
+    fn to_value(&self) -> Value {
+        Value::PersistentHashSet(self.clone())
+    }
+}
 
 /// Allows a type to be evaluated, abstracts evaluation
 ///
@@ -617,37 +1446,116 @@ pub trait Evaluable {
 }
 
 impl Evaluable for Rc<Value> {
-
     fn eval_to_rc(&self, environment: Rc<Environment>) -> Rc<Value> {
-        match &**self {
+	eval_invocation(TailCall(Rc::clone(self),environment))
+    }
+}
+//
+// Rather than recursing into forms in tail position (the body of a fn we're calling, the
+// branch an if chooses, the expansion of a macro),  we loop on them right here, so a chain of
+// tail calls -- self or mutual recursion alike, or a loop's recur -- runs in constant Rust stack.
+// Only forms outside of tail position, like the arguments to a fn, recurse 
+//
+/// Runs an Invocation to completion;  our eval loop 
+///
+/// A Condition coming out of here is traced through the call it was evaluating, and the form
+/// we were asked to evaluate;  calls in tail position in between have already returned to us,
+/// and so, like their Rust stack frames,  are gone from the trace
+pub fn eval_invocation(invocation: Invocation) -> Rc<Value> {
+    let entry_form = match &invocation {
+	TailCall(form,_) => Some(Rc::clone(form)),
+	_ => None
+    };
+    let mut last_call = None;
+    let result = run_invocation(invocation,&mut last_call);
+    match &*result {
+	Value::Condition(condition) => {
+	    let mut condition = condition.clone();
+	    for form in last_call.iter().chain(entry_form.iter()) {
+		let already_traced = condition.trace.last().is_some_and(|last| Rc::ptr_eq(&last.form,form));
+		if let Value::PersistentList(Cons(..)) = &**form {
+		    if !already_traced {
+			condition = condition.traced_through(form);
+		    }
+		}
+	    }
+	    Rc::new(Value::Condition(condition))
+	},
+	_ => result
+    }
+}
+/// Our eval loop proper;  last_call is left holding the last (f args) form it evaluated 
+fn run_invocation(invocation: Invocation,last_call: &mut Option<Rc<Value>>) -> Rc<Value> {
+    let mut invocation = invocation;
+    // The fn or loop whose body we're in,  which a recur jumps back to 
+    let mut recursion_point : Option<Rc<RecursionPoint>> = None;
+    loop {
+	let (form,environment) = match invocation {
+	    Done(value) => return value,
+	    TailCall(form,environment) => (form,environment),
+	    Enter(point,environment) => {
+		let body = Rc::clone(&point.body);
+		recursion_point = Some(point);
+		(body,environment)
+	    },
+	    Recur(args) => match &recursion_point {
+		Some(point) => {
+		    if args.len() != point.params.len() {
+			return Rc::new(condition::raise(format!("Wrong number of arguments given to recur (Given: {}, Expected: {})",args.len(),point.params.len())));
+		    }
+		    let local_environment = Rc::new(Environment::new_local_environment(Rc::clone(&point.environment)));
+		    for (param,arg) in point.params.iter().zip(args) {
+			local_environment.insert(param.clone(),arg);
+		    }
+		    (Rc::clone(&point.body),local_environment)
+		},
+		None => return Rc::new(condition::raise(std::string::String::from("Can only recur from tail position of a fn or loop")))
+	    }
+	};
+	invocation = match &*form {
 	    // Evaluating a symbol means grabbing the value its been bound to in our environment
-            Value::Symbol(symbol) => environment.get(symbol),
+	    Value::Symbol(symbol) => return environment.get(symbol),
 	    // Evaluating a vector [a b c] just means [(eval a) (eval b) (eval c)]
 	    Value::PersistentVector(pvector) => {
 		// Evaluate each Rc<Value> our PersistentVector wraps
 		// and return a new PersistentVector wrapping the new evaluated Values 
-		let evaled_vals =  pvector.vals.iter().map(|rc_val| {
-                    rc_val.eval_to_rc(Rc::clone(&environment))
-                }).collect::<PersistentVector>();
-		Rc::new(Value::PersistentVector(evaled_vals))
+		return match Value::eval_each(pvector.vals.iter().cloned(),&environment) {
+		    Ok(evaled_vals) => Rc::new(Value::PersistentVector(PersistentVector { vals: evaled_vals, meta: pvector.meta.clone() })),
+		    Err(unwinding) => unwinding
+		};
 	    },
 	    Value::PersistentListMap(plistmap) => {
 		// Evaluate each Rc<Value> our PersistentVector wraps
 		// and return a new PersistentVector wrapping the new evaluated Values 
-		let evaled_vals =  plistmap.iter().map(|map_entry| {
-		    MapEntry { key: map_entry.key.eval_to_rc(Rc::clone(&environment)),
-			       val: map_entry.val.eval_to_rc(Rc::clone(&environment))}
-                }).collect::<PersistentListMap>();
-		Rc::new(Value::PersistentListMap(evaled_vals))
+		let mut evaled_vals = vec![];
+		for map_entry in plistmap.iter() {
+		    match Value::eval_each(vec![map_entry.key,map_entry.val].into_iter(),&environment) {
+			Ok(key_val) => evaled_vals.push(MapEntry { key: Rc::clone(&key_val[0]),
+								   val: Rc::clone(&key_val[1])}),
+			Err(unwinding) => return unwinding
+		    }
+		}
+		return Rc::new(Value::PersistentListMap(evaled_vals.into_iter().collect::<PersistentListMap>().with_meta(IMeta::meta(plistmap))));
+	    },
+	    // Likewise #{a b} is #{(eval a) (eval b)} 
+	    Value::PersistentHashSet(phashset) => {
+		return match Value::eval_each(phashset.iter(),&environment) {
+		    Ok(evaled_vals) => Rc::new(Value::PersistentHashSet(evaled_vals.into_iter().collect::<PersistentHashSet>().with_meta(IMeta::meta(phashset)))),
+		    Err(unwinding) => unwinding
+		};
 	    },
 	    // Evaluating a list (a b c) means calling a as a function or macro on arguments b and c 
-            Value::PersistentList(plist) => match plist {
-                Cons(head,tail,__count) => {
+	    Value::PersistentList(plist) => match plist {
+		Cons(head,tail,..) => {
+		    *last_call = Some(Rc::clone(&form));
 		    // First we have to evaluate the head of our list and make sure it is function-like
 		    // and can be invoked on our arguments
 		    // (ie, a fn, a macro, a keyword ..)
 		    // @TODO remove clone if possible 
-                    let ifn = Rc::clone(head).eval_to_rc(Rc::clone(&environment));
+		    let ifn = Rc::clone(head).eval_to_rc(Rc::clone(&environment));
+		    if ifn.is_unwinding() {
+			return ifn;
+		    }
 
 		    let try_apply_ifn = ifn.apply_to_persistent_list(&Rc::clone(&environment),tail);
 
@@ -658,14 +1566,14 @@ impl Evaluable for Rc<Value> {
 		    //
 		    // Sounds less correct but also seems clearer; the current error message relies on
 		    // you pretty much already knowing when this error message is called
-		    try_apply_ifn.unwrap_or(Rc::new(Value::Condition(format!("Execution Error: {} cannot be cast to clojure.lang.IFn",ifn.type_tag()))))
+		    try_apply_ifn.unwrap_or_else(|| Done(Rc::new(condition::raise(format!("Execution Error: {} cannot be cast to clojure.lang.IFn",ifn.type_tag())))))
 		},
 		// () evals to () 
-		PersistentList::Empty => Rc::new(Value::PersistentList(PersistentList::Empty))
-            },
+		PersistentList::Empty(_) => return Rc::clone(&form)
+	    },
 	    // Other types eval to self; (5 => 5,  "cat" => "cat",  #function[+] => #function[+]
-            _ => Rc::clone(&self),
-        }
+	    _ => return Rc::clone(&form),
+	};
     }
 }
 impl Evaluable for PersistentList {
@@ -678,3 +1586,683 @@ impl Evaluable for Value {
         self.to_rc_value().eval_to_rc(environment)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::Environment;
+    use crate::reader;
+    use crate::repl;
+    use crate::source::Source;
+    use crate::keyword::Keyword;
+    use crate::value::{Value,ToValue,Evaluable};
+    use crate::condition;
+    use crate::condition::{Condition,Restart};
+    use std::rc::Rc;
+    use std::hash::{Hash,Hasher};
+
+    fn eval_str(input: &str) -> Value {
+	let environment = Environment::clojure_core_environment();
+	let (_,form) = reader::try_read(input.as_bytes()).unwrap();
+	form.eval(environment)
+    }
+
+    /// Reads and evaluates every form in input,  returning the value of the last 
+    fn eval_all(environment: &Rc<Environment>,input: &str) -> Value {
+	let mut rest_input = input.as_bytes();
+	let mut last_value = Value::Nil;
+	while let Ok((_rest_input,form)) = reader::try_read(rest_input) {
+	    last_value = form.eval(Rc::clone(environment));
+	    rest_input = _rest_input;
+	}
+	last_value
+    }
+
+    /// Our environment,  with clojure.core loaded 
+    fn core_environment() -> Rc<Environment> {
+	let environment = Environment::clojure_core_environment();
+	repl::try_eval_file(&environment,"./src/clojure/core.clj").unwrap();
+	environment
+    }
+
+    #[test]
+    fn test_if()
+    {
+	assert_eq!(eval_str("(if true 1 2)"),Value::I64(1));
+	assert_eq!(eval_str("(if false 1 2)"),Value::I64(2));
+	assert_eq!(eval_str("(if nil 1 2)"),Value::I64(2));
+	assert_eq!(eval_str("(if false 1)"),Value::Nil);
+	// Only nil and false are falsey 
+	assert_eq!(eval_str("(if 0 1 2)"),Value::I64(1));
+	assert_eq!(eval_str("(if \"\" 1 2)"),Value::I64(1));
+	assert_eq!(eval_str("(if (quote ()) 1 2)"),Value::I64(1));
+	// Only the chosen branch is evaluated 
+	assert_eq!(eval_str("(if true 1 undefined-symbol)"),Value::I64(1));
+	assert_eq!(eval_str("(if true 1 2 3)").type_tag().to_string(),"clojure.lang.Condition");
+    }
+
+    // Counts down from n by calling itself;  deep enough,  and this would overflow the Rust
+    // stack if each call took a frame of its own 
+    fn count_down(n: usize) -> Value {
+	let environment = core_environment();
+	eval_all(&environment,&format!("(defn count-down [n]
+                                          (if (zero? n)
+                                            \"done\"
+                                            (count-down (dec n))))
+                                        (count-down {})",n))
+    }
+    #[test]
+    fn test_self_tail_call_runs_in_constant_stack()
+    {
+	assert_eq!(count_down(20000),Value::String(std::string::String::from("done")));
+    }
+    // Slow;  cargo test -- --ignored 
+    #[test]
+    #[ignore]
+    fn test_self_tail_call_runs_in_constant_stack_a_million_deep()
+    {
+	assert_eq!(count_down(1000000),Value::String(std::string::String::from("done")));
+    }
+
+    #[test]
+    fn test_mutual_tail_calls_run_in_constant_stack()
+    {
+	let environment = core_environment();
+	let result = eval_all(&environment,
+			      "(defn my-even? [n] (if (zero? n) true (my-odd? (dec n))))
+                               (defn my-odd? [n] (if (zero? n) false (my-even? (dec n))))
+                               (my-even? 20001)");
+	assert_eq!(result,Value::Boolean(false));
+    }
+
+    #[test]
+    fn test_tail_position_through_let_and_do()
+    {
+	let environment = core_environment();
+	let result = eval_all(&environment,
+			      "(defn count-down [n]
+                                 (do
+                                   (str n)
+                                   (let [m (dec n)]
+                                     (if (zero? n) n (count-down m)))))
+                               (count-down 20000)");
+	assert_eq!(result,Value::I64(0));
+	assert_eq!(eval_str("(do)"),Value::Nil);
+	assert_eq!(eval_str("(do 1 2 3)"),Value::I64(3));
+    }
+
+    #[test]
+    fn test_loop_recur()
+    {
+	let environment = core_environment();
+	assert_eq!(eval_all(&environment,"(loop [i 20000 acc 0] (if (zero? i) acc (recur (dec i) (+ acc 1))))"),
+		   Value::I64(20000));
+	// Bindings are sequential,  like let 
+	assert_eq!(eval_all(&environment,"(loop [i 3 j (dec i)] (if (zero? i) j (recur (dec i) j)))"),
+		   Value::I64(2));
+	// recur also targets the enclosing fn,  including past a & 
+	assert_eq!(eval_all(&environment,"(defn count-down [n] (if (zero? n) \"done\" (recur (dec n))))
+                                          (count-down 20000)"),
+		   Value::String(std::string::String::from("done")));
+	assert_eq!(eval_all(&environment,"((fn [n & more] (if (zero? n) more (recur (dec n) (concat more (list n))))) 3)"),
+		   eval_all(&environment,"(list 3 2 1)"));
+	// A loop inside a fn is checked along with it,  once,  rather than each time it runs 
+	assert_eq!(eval_all(&environment,"(defn sum-to [n] (loop [i n acc 0] (if (zero? i) acc (recur (dec i) (+ acc i)))))
+                                          (+ (sum-to 10) (sum-to 100))"),
+		   Value::I64(5105));
+	// A recur in a nested loop targets that loop,  not the outer fn 
+	assert_eq!(eval_all(&environment,"((fn [n] (loop [i n] (if (zero? i) (quote inner) (recur (dec i))))) 5)"),
+		   Value::Symbol(crate::symbol::Symbol::intern("inner")));
+    }
+
+    #[test]
+    fn test_recur_errors()
+    {
+	let environment = core_environment();
+	let non_tail = eval_all(&environment,"(fn [x] (+ 1 (recur x)))").to_string();
+	assert!(non_tail.contains("Can only recur from tail position"));
+	assert!(non_tail.contains("(+ 1 (recur x))"));
+	// A fn inside another is checked when the outer one is built,  before it ever runs 
+	assert!(eval_all(&environment,"(fn [] (fn [x] (+ 1 (recur x))))").to_string().contains("Can only recur from tail position"));
+	assert!(eval_all(&environment,"(fn [] (loop [x 1] (+ 1 (recur x))))").to_string().contains("Can only recur from tail position"));
+	// Macros are expanded before we check;  defn puts our body in tail position, so this is fine 
+	assert_eq!(eval_all(&environment,"(defn f [n] (if (zero? n) n (recur (dec n)))) (f 3)"),Value::I64(0));
+	// .. but not in the test of an if 
+	let in_test = eval_all(&environment,"(defn g [n] (if (recur n) 1 2))").to_string();
+	assert!(in_test.contains("Can only recur from tail position"));
+	assert!(eval_all(&environment,"(loop [i 0] (do (recur i) 1))").to_string().contains("Can only recur from tail position"));
+	// Macros in a body aren't expanded just to check it;  only once they're evaluated.  Our
+	// debugger hook counts each time noisy's expansion signals 
+	let expansions = Rc::new(std::cell::Cell::new(0));
+	let counted_expansions = Rc::clone(&expansions);
+	condition::set_debugger_hook(Some(Rc::new(move |_condition: &Condition,_restarts: &[Restart]| {
+	    counted_expansions.set(counted_expansions.get() + 1);
+	    None
+	})));
+	eval_all(&environment,"(defmacro noisy [] (error :expanded))");
+	assert_eq!(eval_all(&environment,"(def h (fn [] (noisy)))").type_tag().to_string(),"clojure.lang.Symbol");
+	assert_eq!(expansions.get(),0);
+	eval_all(&environment,"(h)");
+	assert_eq!(expansions.get(),1);
+	condition::set_debugger_hook(None);
+	// .. so a recur one puts out of tail position is caught as it runs 
+	eval_all(&environment,"(defmacro twice [x] (list (quote do) x x))");
+	assert!(eval_all(&environment,"((fn [n] (twice (recur n))) 1)").to_string().contains("Can only recur from tail position"));
+	assert_eq!(eval_all(&environment,"(loop [i 0] (recur 1 2))"),
+		   Value::Condition(Condition::new(std::string::String::from("Wrong number of arguments given to recur (Given: 2, Expected: 1)"))));
+	assert_eq!(eval_all(&environment,"((fn [x y] (if x (recur false) y)) true 2)"),
+		   Value::Condition(Condition::new(std::string::String::from("Wrong number of arguments given to recur (Given: 1, Expected: 2)"))));
+    }
+
+    #[test]
+    fn test_handlers_invoke_restarts()
+    {
+	let environment = core_environment();
+	eval_all(&environment,
+		 "(defn div [x y]
+                    (restart-case
+                      (if (zero? y) (+ 1 (error :divide-by-zero {:y y})) x)
+                      (return-zero [] 0)
+                      (return-value [r] r)))");
+	assert_eq!(eval_all(&environment,
+			    "(handler-bind :divide-by-zero (fn [c] (invoke-restart (quote return-value) 10))
+                               (div 5 0))"),
+		   Value::I64(10));
+	assert_eq!(eval_all(&environment,
+			    "(handler-bind :divide-by-zero (fn [c] (invoke-restart (quote return-zero)))
+                               (div 5 0))"),
+		   Value::I64(0));
+	// Handlers that don't match our type,  or that decline by returning,  are passed over 
+	assert_eq!(eval_all(&environment,
+			    "(handler-bind :divide-by-zero (fn [c] (invoke-restart (quote return-value) 1))
+                               (handler-bind :other-error (fn [c] (invoke-restart (quote return-zero)))
+                                             :divide-by-zero (fn [c] nil)
+                                 (div 5 0)))"),
+		   Value::I64(1));
+	// No handler,  no debugger hook;  we're just left with our condition 
+	assert_eq!(eval_all(&environment,"(restart-case (error :divide-by-zero) (return-zero [] 0))"),
+		   Value::Condition(Condition {
+		       condition_type: Keyword::intern("divide-by-zero").to_rc_value(),
+		       ..Condition::new(std::string::String::from(":divide-by-zero"))
+		   }));
+	// Our types are keywords,  and our data a map 
+	assert!(eval_all(&environment,"(error (quote divide-by-zero))").to_string().contains("Expected instance of clojure.lang.Keyword"));
+	assert!(eval_all(&environment,"(error :divide-by-zero 0)").to_string().contains("Expected instance of clojure.lang.PersistentListMap"));
+	assert!(eval_all(&environment,"(handler-bind divide-by-zero (fn [c] nil) 1)").to_string().contains("Handler type must be a keyword"));
+	assert_eq!(eval_all(&environment,"(invoke-restart (quote return-zero))"),
+		   Value::Condition(Condition::new(std::string::String::from("No restart named return-zero is active"))));
+    }
+    #[test]
+    fn test_builtin_conditions_are_signalled()
+    {
+	let environment = core_environment();
+	assert_eq!(eval_all(&environment,
+			    "(handler-bind :arithmetic-error (fn [c] (invoke-restart 'use-value 42))
+                               (restart-case (/ 1 0) (use-value [v] v)))"),
+		   Value::I64(42));
+	// Our untyped ones too,  though only the debugger hook gets to see those
+	condition::set_debugger_hook(Some(Rc::new(|condition: &Condition,restarts: &[Restart]| {
+	    assert_eq!(condition.message,"Undefined symbol y");
+	    restarts.first().map(|restart| restart.invocation(vec![Rc::new(Value::I64(7))]))
+	})));
+	assert_eq!(eval_all(&environment,"(restart-case (+ 1 y) (use-value [v] v))"),Value::I64(7));
+	condition::set_debugger_hook(None);
+    }
+    #[test]
+    fn test_debugger_hook_chooses_restart()
+    {
+	let environment = core_environment();
+	condition::set_debugger_hook(Some(Rc::new(|_condition: &Condition,restarts: &[Restart]| {
+	    restarts.iter().find(|restart| restart.name == "return-value")
+		.map(|restart| restart.invocation(vec![Rc::new(Value::I64(42))]))
+	})));
+	assert_eq!(eval_all(&environment,
+			    "(restart-case (error :oops) (return-value [r] r))"),
+		   Value::I64(42));
+	condition::set_debugger_hook(None);
+    }
+
+    #[test]
+    fn test_conditions_short_circuit()
+    {
+	let environment = core_environment();
+	let undefined = Value::Condition(Condition::new(std::string::String::from("Undefined symbol y")));
+	// Rather than "Type mismatch" from + itself 
+	assert_eq!(eval_all(&environment,"(+ 1 y)"),undefined);
+	assert_eq!(eval_all(&environment,"(let [x y] (print-string x))"),undefined);
+	assert_eq!(eval_all(&environment,"(do y (print-string 1))"),undefined);
+	match eval_all(&environment,"(defn f [x] (+ x y)) (str 1 (f 2))") {
+	    Value::Condition(condition) => {
+		let trace = condition.trace.iter().map(|frame| frame.form.to_string()).collect::<Vec<std::string::String>>();
+		assert_eq!(trace,vec!["(+ x y)","(f 2)","(str 1 (f 2))"]);
+	    },
+	    value => panic!("Expected a condition, got: {}",value)
+	}
+    }
+
+    #[test]
+    fn test_ex_info()
+    {
+	let environment = core_environment();
+	eval_all(&environment,"(def cause (ex-info \"inner\" {}))
+                                (def condition (ex-info \"outer\" {(quote x) 1} cause))");
+	assert_eq!(eval_all(&environment,"(ex-message condition)"),Value::String(std::string::String::from("outer")));
+	assert_eq!(eval_all(&environment,"(ex-data condition)"),eval_all(&environment,"{(quote x) 1}"));
+	assert_eq!(eval_all(&environment,"(ex-message (ex-cause condition))"),Value::String(std::string::String::from("inner")));
+	assert_eq!(eval_all(&environment,"(ex-cause cause)"),Value::Nil);
+	assert_eq!(eval_all(&environment,"(ex-data 1)"),Value::Nil);
+	// Handlers are given what error was given 
+	assert_eq!(eval_all(&environment,
+			    "(restart-case
+                               (handler-bind :oops (fn [c] (invoke-restart (quote use-data) (ex-data c)))
+                                 (error :oops {:x [1 2]}))
+                               (use-data [data] data))"),
+		   eval_all(&environment,"{:x [1 2]}"));
+	match eval_all(&environment,"(error :oops {:x [1 2]})") {
+	    Value::Condition(condition) => assert_eq!(condition.report(),
+						      "#Condition[\":oops {:x [1 2]}\"]\n  type: :oops\n  data: {:x [1 2]}\n  at error"),
+	    value => panic!("Expected a condition, got: {}",value)
+	}
+    }
+
+    #[test]
+    fn test_try_catch_finally()
+    {
+	let environment = core_environment();
+	assert_eq!(eval_all(&environment,"(try (+ 1 (error :oops)) (catch :oops e 2))"),Value::I64(2));
+	assert_eq!(eval_all(&environment,"(try undefined-sym (catch :other e 1) (catch Exception e (ex-message e)))"),
+		   Value::String(std::string::String::from("Undefined symbol undefined-sym")));
+	assert_eq!(eval_all(&environment,"(try (throw (ex-info \"boom\" {(quote a) 1})) (catch Throwable e (ex-data e)))"),
+		   eval_all(&environment,"{(quote a) 1}"));
+	assert_eq!(eval_all(&environment,"(try (throw (ex-info \"x\" {})) (catch clojure.lang.ExceptionInfo e (ex-message e)))"),
+		   Value::String(std::string::String::from("x")));
+	assert_eq!(eval_all(&environment,"(try (error :oops {:y 1}) (catch ExceptionInfo e (ex-data e)))"),
+		   eval_all(&environment,"{:y 1}"));
+	// ExceptionInfo only catches what carries data;  and a class name is never qualified
+	assert_eq!(eval_all(&environment,"(try (/ 1 0) (catch ExceptionInfo e 1) (catch Exception e 2))"),Value::I64(2));
+	assert_eq!(eval_all(&environment,"(try (/ 1 0) (catch foo/Exception e 1))").type_tag().to_string(),"clojure.lang.Condition");
+	// Nothing catches it;  on it goes
+	assert_eq!(eval_all(&environment,"(try (error :oops) (catch :other e 1))").type_tag().to_string(),"clojure.lang.Condition");
+	// finally always runs,  but doesn't decide our value 
+	assert_eq!(eval_all(&environment,"(try 1 (finally (def cleaned-up 1) 2))"),Value::I64(1));
+	assert_eq!(eval_all(&environment,"(try (error :oops) (catch :oops e (def cleaned-up 2)) (finally (def cleaned-up 3))) cleaned-up"),
+		   Value::I64(3));
+	assert_eq!(eval_all(&environment,"(try (try (error :oops) (finally (def cleaned-up 4))) (catch :oops e cleaned-up))"),
+		   Value::I64(4));
+	assert_eq!(eval_all(&environment,"(throw 1)").type_tag().to_string(),"clojure.lang.Condition");
+	// What a catch catches never makes it out to the handlers and debugger hook beyond it 
+	let hooked = Rc::new(std::cell::Cell::new(0));
+	let counted_hooked = Rc::clone(&hooked);
+	condition::set_debugger_hook(Some(Rc::new(move |_condition: &Condition,restarts: &[Restart]| {
+	    counted_hooked.set(counted_hooked.get() + 1);
+	    restarts.iter().find(|restart| restart.name == "use-value")
+		.map(|restart| restart.invocation(vec![Rc::new(Value::I64(1))]))
+	})));
+	assert_eq!(eval_all(&environment,"(restart-case (try (error :boom) (catch :boom e :caught)) (use-value [v] v))"),
+		   Keyword::intern("caught").to_value());
+	assert_eq!(eval_all(&environment,
+			    "(restart-case
+                               (handler-bind :boom (fn [c] (invoke-restart (quote use-value) 2))
+                                 (try (error :boom) (catch Exception e :caught)))
+                               (use-value [v] v))"),
+		   Keyword::intern("caught").to_value());
+	assert_eq!(hooked.get(),0);
+	// .. while what it doesn't still does 
+	assert_eq!(eval_all(&environment,"(restart-case (try (error :boom) (catch :other e :caught)) (use-value [v] v))"),
+		   Value::I64(1));
+	assert_eq!(hooked.get(),1);
+	condition::set_debugger_hook(None);
+    }
+    #[test]
+    fn test_rethrow_preserves_trace()
+    {
+	let environment = core_environment();
+	match eval_all(&environment,"(defn f [x] (+ x y)) (try (str (f 1)) (catch Exception e (throw e)))") {
+	    Value::Condition(condition) => {
+		let trace = condition.trace.iter().map(|frame| frame.name.clone()).collect::<Vec<std::string::String>>();
+		assert_eq!(trace,vec!["+","f","str","throw","try"]);
+	    },
+	    value => panic!("Expected a condition, got: {}",value)
+	}
+    }
+
+    #[test]
+    fn test_add_promotes()
+    {
+	assert_eq!(eval_str("(+ 1 2)"),Value::I64(3));
+	assert_eq!(eval_str("(+ 1 2.5)"),Value::F64(3.5));
+	assert_eq!(eval_str("(+ -0x10 2r11 1e1)"),Value::F64(-3.0));
+	assert_eq!(eval_str("(+ 1 ##Inf)"),Value::F64(f64::INFINITY));
+	assert_eq!(eval_str("(+ 9223372036854775807 1)").type_tag().to_string(),"clojure.lang.Condition");
+	assert_eq!(eval_str("1.0").to_string(),"1.0");
+	assert_eq!(eval_str("##-Inf").to_string(),"##-Inf");
+    }
+    #[test]
+    fn test_number_equality_agrees_with_hash()
+    {
+	let hash = |value: &Value| {
+	    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+	    value.hash(&mut hasher);
+	    hasher.finish()
+	};
+	assert_eq!(Value::F64(0.0),Value::F64(-0.0));
+	assert_eq!(Value::I64(1),Value::BigInt(1.into()));
+	assert_eq!(hash(&Value::I64(1)),hash(&Value::BigInt(1.into())));
+	assert_eq!(hash(&Value::I32(1)),hash(&Value::BigInt(1.into())));
+	assert_eq!(eval_str("(count (hash-set 1 1N (+' 9223372036854775807 1) 9223372036854775808N))"),Value::I64(2));
+	assert_eq!(hash(&Value::F64(0.0)),hash(&Value::F64(-0.0)));
+	assert_eq!(Value::F64(f64::NAN),Value::F64(-f64::NAN));
+	assert_eq!(hash(&Value::F64(f64::NAN)),hash(&Value::F64(-f64::NAN)));
+	assert_eq!(eval_str("(count (hash-set 0.0 -0.0 ##NaN ##NaN))"),Value::I64(2));
+	// = is still numeric equality 
+	assert_eq!(eval_str("(= 0.0 -0.0)"),Value::Boolean(true));
+	assert_eq!(eval_str("(= ##NaN ##NaN)"),Value::Boolean(false));
+    }
+
+    #[test]
+    fn test_arithmetic()
+    {
+	assert_eq!(eval_str("(- 10 1 2)"),Value::I64(7));
+	assert_eq!(eval_str("(- 5)"),Value::I64(-5));
+	assert_eq!(eval_str("(* 2 3 4)"),Value::I64(24));
+	assert_eq!(eval_str("(* 2 1.5)"),Value::F64(3.0));
+	assert_eq!(eval_str("(/ 12 2 3)"),Value::I64(2));
+	assert_eq!(eval_str("(/ 4)").to_string(),"1/4");
+	assert_eq!(eval_str("(/ 1.0 0)"),Value::F64(f64::INFINITY));
+	assert_eq!(eval_str("(quot -7 2)"),Value::I64(-3));
+	assert_eq!(eval_str("(rem -7 2)"),Value::I64(-1));
+	assert_eq!(eval_str("(mod -7 2)"),Value::I64(1));
+	assert_eq!(eval_str("(mod 7 -2)"),Value::I64(-1));
+	assert_eq!(eval_str("(mod -7.5 2)"),Value::F64(0.5));
+	assert_eq!(eval_str("(inc 1)"),Value::I64(2));
+	assert_eq!(eval_str("(dec 1.5)"),Value::F64(0.5));
+	assert_eq!(eval_str("(max 1 3.5 2)"),Value::F64(3.5));
+	assert_eq!(eval_str("(min 1 -3 2)"),Value::I64(-3));
+	// Arithmetic errors are conditions,  not panics 
+	for input in ["(/ 1 0)","(quot 1 0)","(mod 1.5 0)","(inc 9223372036854775807)","(* 9223372036854775807 2)","(- -9223372036854775807 2)"].iter() {
+	    match eval_str(input) {
+		Value::Condition(condition) => assert_eq!(condition.condition_type,Keyword::intern("arithmetic-error").to_rc_value()),
+		value => panic!("Expected an arithmetic condition from {}, got: {}",input,value)
+	    }
+	}
+	assert_eq!(eval_str("(+ 1 (quote a))").type_tag().to_string(),"clojure.lang.Condition");
+    }
+    #[test]
+    fn test_comparison()
+    {
+	assert_eq!(eval_str("(= 1 1 1)"),Value::Boolean(true));
+	assert_eq!(eval_str("(= 1 1.0)"),Value::Boolean(false));
+	assert_eq!(eval_str("(== 1 1.0)"),Value::Boolean(true));
+	assert_eq!(eval_str("(= [1 2] [1 2])"),Value::Boolean(true));
+	assert_eq!(eval_str("(not= 1 2)"),Value::Boolean(true));
+	assert_eq!(eval_str("(< 1 2 3)"),Value::Boolean(true));
+	assert_eq!(eval_str("(< 1 3 2)"),Value::Boolean(false));
+	assert_eq!(eval_str("(<= 1 1 2.5)"),Value::Boolean(true));
+	assert_eq!(eval_str("(> 3 2 1)"),Value::Boolean(true));
+	assert_eq!(eval_str("(>= 3 3 4)"),Value::Boolean(false));
+	assert_eq!(eval_str("(< 1 ##NaN)"),Value::Boolean(false));
+	assert_eq!(eval_str("(zero? 0.0)"),Value::Boolean(true));
+	assert_eq!(eval_str("(pos? -1)"),Value::Boolean(false));
+	assert_eq!(eval_str("(neg? -1)"),Value::Boolean(true));
+    }
+    #[test]
+    fn test_bigints_and_ratios()
+    {
+	let eval_to_string = |input| eval_str(input).to_string();
+	assert_eq!(eval_to_string("(/ 1 3)"),"1/3");
+	assert_eq!(eval_to_string("(+ 1/3 2/3)"),"1N");
+	assert_eq!(eval_to_string("(* 1/3 0.5)"),"0.16666666666666666");
+	assert_eq!(eval_to_string("(/ 4N 2)"),"2N");
+	assert_eq!(eval_to_string("(+' 9223372036854775807 1)"),"9223372036854775808N");
+	assert_eq!(eval_to_string("(*' 9223372036854775807 2)"),"18446744073709551614N");
+	assert_eq!(eval_to_string("(dec' -9223372036854775808)"),"-9223372036854775809N");
+	assert_eq!(eval_to_string("(+' 1 2)"),"3");
+	assert_eq!(eval_to_string("(numerator 2/6)"),"1N");
+	assert_eq!(eval_to_string("(denominator 2/6)"),"3N");
+	assert_eq!(eval_to_string("(bigint 3.7)"),"3N");
+	assert_eq!(eval_to_string("(rationalize 0.1)"),"1/10");
+	assert_eq!(eval_to_string("(rationalize 2.0)"),"2N");
+	assert_eq!(eval_to_string("(quot 7/2 1)"),"3N");
+	assert_eq!(eval_to_string("(mod -7/2 2)"),"1/2");
+	assert_eq!(eval_str("(= 1 1N)"),Value::Boolean(true));
+	assert_eq!(eval_str("(= 1/2 0.5)"),Value::Boolean(false));
+	assert_eq!(eval_str("(== 1/2 0.5)"),Value::Boolean(true));
+	assert_eq!(eval_str("(< 1/3 0.5 1N)"),Value::Boolean(true));
+	assert_eq!(eval_str("(/ 1/3 0)").type_tag().to_string(),"clojure.lang.Condition");
+	assert_eq!(eval_str("(numerator 1)").type_tag().to_string(),"clojure.lang.Condition");
+    }
+    #[test]
+    fn test_keyword_lookup()
+    {
+	assert_eq!(eval_str("(:name {:name \"Blah\" :age 20})"),Value::String(std::string::String::from("Blah")));
+	assert_eq!(eval_str("(:height {:name \"Blah\"})"),Value::Nil);
+	assert_eq!(eval_str("(:height {:name \"Blah\"} 180)"),Value::I64(180));
+	// A key that's there,  but mapped to nil,  is still there 
+	assert_eq!(eval_str("(:height {:height nil} 180)"),Value::Nil);
+	assert_eq!(eval_str("(:name 5)"),Value::Nil);
+	assert_eq!(eval_str(":name").to_string(),":name");
+	assert_eq!(eval_str("(:name)").type_tag().to_string(),"clojure.lang.Condition");
+    }
+    #[test]
+    fn test_invoke_collections()
+    {
+	assert_eq!(eval_str("({:name \"Blah\"} :name)"),Value::String(std::string::String::from("Blah")));
+	assert_eq!(eval_str("({:name \"Blah\"} :age)"),Value::Nil);
+	assert_eq!(eval_str("({:name \"Blah\"} :age 20)"),Value::I64(20));
+	assert_eq!(eval_str("([:a :b :c] 1)").to_string(),":b");
+	assert_eq!(eval_str("([:a :b :c] (inc 1))").to_string(),":c");
+	match eval_str("([:a :b :c] 3)") {
+	    Value::Condition(condition) => assert_eq!(condition.message,"Index out of bounds: Index (3), Length: (3)"),
+	    value => panic!("Expected an out of bounds condition, got: {}",value)
+	}
+	assert_eq!(eval_str("([:a] :a)").type_tag().to_string(),"clojure.lang.Condition");
+    }
+    #[test]
+    fn test_syntax_quote()
+    {
+	let environment = core_environment();
+	eval_all(&environment,"(defmacro unless [test & body] `(if ~test nil (do ~@body)))
+                               (defmacro swap-let [a b] `(let [tmp# ~a] [~b tmp#]))
+                               (defn f [] :f)");
+	assert_eq!(eval_all(&environment,"(unless false 1 2)"),Value::I64(2));
+	assert_eq!(eval_all(&environment,"(unless true 1 2)"),Value::Nil);
+	// Our tmp# can't capture the caller's tmp 
+	assert_eq!(eval_all(&environment,"(let [tmp 1] (swap-let tmp 2))").to_string(),"[2 1]");
+	assert_eq!(eval_all(&environment,"`[1 ~(inc 1) ~@(list 3 4)]").to_string(),"[1 2 3 4]");
+	assert_eq!(eval_all(&environment,"`{:a ~(inc 1)}").to_string(),"{:a 2}");
+	// Whatever concat and vec mean where we expand 
+	assert_eq!(eval_all(&environment,"(let [concat nil vec nil] `[1 ~@(list 2 3)])").to_string(),"[1 2 3]");
+	// user/f,  in the namespace user,  is f 
+	assert_eq!(eval_all(&environment,"(eval `(f))").to_string(),":f");
+    }
+    #[test]
+    fn test_chars_and_strings_as_seqs()
+    {
+	assert_eq!(eval_str("(char 65)"),Value::Char('A'));
+	assert_eq!(eval_str("(int \\A)"),Value::I64(65));
+	assert_eq!(eval_str("(int 2.7)"),Value::I64(2));
+	assert_eq!(eval_str("(char? \\A)"),Value::Boolean(true));
+	assert_eq!(eval_str("(char? \"A\")"),Value::Boolean(false));
+	assert_eq!(eval_str("(nth \"Ωmega\" 1)"),Value::Char('m'));
+	assert_eq!(eval_str("(first \"Ωmega\")"),Value::Char('Ω'));
+	assert_eq!(eval_str("(first \"\")"),Value::Nil);
+	assert_eq!(eval_str("(count \"Ωmega\")"),Value::I64(5));
+	assert_eq!(eval_str("(count [1 2 3])"),Value::I64(3));
+	assert_eq!(eval_str("(concat \"ab\" [\\c])").to_string_explicit(),"(\\a \\b \\c)");
+	assert_eq!(eval_str("(str \\a \"b\" \\newline)"),Value::String(std::string::String::from("ab\n")));
+	assert_eq!(eval_str("(char -1)").type_tag().to_string(),"clojure.lang.Condition");
+    }
+    #[test]
+    fn test_fn_literals()
+    {
+	assert_eq!(eval_str("(#(+ % 1) 2)"),Value::I64(3));
+	assert_eq!(eval_str("(#(- %2 %1) 1 10)"),Value::I64(9));
+	assert_eq!(eval_str("(#(concat [%] %&) 1 2 3)").to_string(),"(1 2 3)");
+	assert_eq!(eval_str("(#(count %&))"),Value::I64(0));
+	assert_eq!(eval_str("(#(str \"a\"))"),Value::String(std::string::String::from("a")));
+    }
+    #[test]
+    fn test_sets()
+    {
+	assert_eq!(eval_str("#{(inc 1) 3}").to_string(),"#{2 3}");
+	assert_eq!(eval_str("(= #{1 2} (set [2 1 2]) (hash-set 2 1))"),Value::Boolean(true));
+	assert_eq!(eval_str("(#{:a :b} :a)").to_string(),":a");
+	assert_eq!(eval_str("(#{:a :b} :c)"),Value::Nil);
+	assert_eq!(eval_str("(:b #{:a :b})").to_string(),":b");
+	assert_eq!(eval_str("(conj #{1} 2 1)").to_string(),"#{1 2}");
+	assert_eq!(eval_str("(conj [1] 2 3)").to_string(),"[1 2 3]");
+	assert_eq!(eval_str("(conj (quote (1)) 2 3)").to_string(),"(3 2 1)");
+	assert_eq!(eval_str("(conj nil 1)").to_string(),"(1)");
+	assert_eq!(eval_str("(:b (conj {:a 1} [:b 2]))"),Value::I64(2));
+	assert_eq!(eval_str("(disj #{1 2 3} 1 2)").to_string(),"#{3}");
+	assert_eq!(eval_str("(contains? #{1 2} 2)"),Value::Boolean(true));
+	assert_eq!(eval_str("(contains? {:a nil} :a)"),Value::Boolean(true));
+	assert_eq!(eval_str("(contains? [:a :b] 1)"),Value::Boolean(true));
+	assert_eq!(eval_str("(contains? [:a :b] :a)"),Value::Boolean(false));
+	assert_eq!(eval_str("(count #{1 2 3})"),Value::I64(3));
+	assert_eq!(eval_str("(disj [1] 1)").type_tag().to_string(),"clojure.lang.Condition");
+    }
+    #[test]
+    fn test_clojure_set()
+    {
+	assert_eq!(eval_str("(clojure.set/union #{1 2} #{2 3})").to_string(),"#{1 2 3}");
+	assert_eq!(eval_str("(clojure.set/union)").to_string(),"#{}");
+	assert_eq!(eval_str("(clojure.set/intersection #{1 2 3} #{2 3 4} #{3 2})").to_string(),"#{2 3}");
+	assert_eq!(eval_str("(clojure.set/difference #{1 2 3} #{2} #{3})").to_string(),"#{1}");
+	assert_eq!(eval_str("(clojure.set/select pos? #{-1 1 2})").to_string(),"#{1 2}");
+	assert_eq!(eval_str("(clojure.set/select #(< % 0) #{-1 1 2})").to_string(),"#{-1}");
+	assert_eq!(eval_str("(clojure.set/project #{{:a 1 :b 2} {:a 1 :b 3}} [:a])").to_string(),"#{{:a 1}}");
+	let renamed = "(clojure.set/rename-keys {:a 1 :b 2} {:a :c :b :a})";
+	assert_eq!(eval_str(&format!("[(:a {0}) (:b {0}) (:c {0})]",renamed)).to_string(),"[2 nil 1]");
+	let index = "(clojure.set/index #{{:a 1 :b 2} {:a 1 :b 3} {:a 2 :b 2}} [:a])";
+	assert_eq!(eval_str(&format!("(count ({} {{:a 1}}))",index)),Value::I64(2));
+	assert_eq!(eval_str(&format!("(count {})",index)),Value::I64(2));
+    }
+    #[test]
+    fn test_meta()
+    {
+	assert_eq!(eval_str("(meta (with-meta [1 2] {:a 1}))").to_string(),"{:a 1}");
+	assert_eq!(eval_str("(meta [1 2])"),Value::Nil);
+	assert_eq!(eval_str("(meta ^:private [])").to_string(),"{:private true}");
+	// Empty lists and maps too 
+	assert_eq!(eval_str("(meta (with-meta () {:a 1}))").to_string(),"{:a 1}");
+	assert_eq!(eval_str("(meta (with-meta {} {:a 1}))").to_string(),"{:a 1}");
+	assert_eq!(eval_str("(meta ^:m {})").to_string(),"{:m true}");
+	assert_eq!(eval_str("(= (with-meta () {:a 1}) ())"),Value::Boolean(true));
+	assert_eq!(eval_str("(meta (quote ^:private x))").to_string(),"{:private true}");
+	assert_eq!(eval_str("(meta (vary-meta ^{:a 1} #{} conj [:b 2]))"),eval_str("{:a 1 :b 2}"));
+	// Metadata is never part of the value 
+	assert_eq!(eval_str("(= (with-meta [1] {:a 1}) [1] (with-meta [1] {:b 2}))"),Value::Boolean(true));
+	assert_eq!(eval_str("(= (hash (with-meta (quote (1 2)) {:a 1})) (hash (quote (1 2))))"),Value::Boolean(true));
+	assert_eq!(eval_str("(= {:a 1 :b 2} {:b 2 :a 1})"),Value::Boolean(true));
+	assert_eq!(eval_str("(with-meta 1 {:a 1})").type_tag().to_string(),"clojure.lang.Condition");
+	// A fn with metadata is still the same fn 
+	assert_eq!(eval_str("((with-meta (fn [x] (inc x)) {:a 1}) 1)"),Value::I64(2));
+	assert_eq!(eval_str("(meta (with-meta inc {:a 1}))").to_string(),"{:a 1}");
+    }
+    #[test]
+    fn test_def_meta()
+    {
+	let environment = core_environment();
+	eval_all(&environment,"(def ^{:doc \"Is x\"} x [])
+                               (def y \"Is y\" [])
+                               (defn ^:private add-one [n] (+ n 1))");
+	assert_eq!(eval_all(&environment,"(:doc (meta x))").to_string(),"Is x");
+	assert_eq!(eval_all(&environment,"(:doc (meta y))").to_string(),"Is y");
+	assert_eq!(eval_all(&environment,"(= (meta add-one) {:private true :arglists (quote ([n]))})"),Value::Boolean(true));
+	assert_eq!(eval_all(&environment,"(add-one 1)"),Value::I64(2));
+	// defn takes a doc-string and attr-map,  as def takes a doc-string
+	eval_all(&environment,"(defn ^:private foo \"docs\" [x] x)
+                               (defn bar {:added \"1\"} [x] (+ x 1))");
+	assert_eq!(eval_all(&environment,"(= (meta foo) {:private true :doc \"docs\" :arglists (quote ([x]))})"),Value::Boolean(true));
+	assert_eq!(eval_all(&environment,"(= (meta bar) {:added \"1\" :arglists (quote ([x]))})"),Value::Boolean(true));
+	assert_eq!(eval_all(&environment,"(+ (foo 1) (bar 1))"),Value::I64(3));
+	assert_eq!(eval_all(&environment,"(def z 1 2)").type_tag().to_string(),"clojure.lang.Condition");
+    }
+    #[test]
+    fn test_qualified_symbols()
+    {
+	let environment = core_environment();
+	eval_all(&environment,"(def x 1)");
+	assert_eq!(eval_all(&environment,"user/x"),Value::I64(1));
+	assert_eq!(eval_all(&environment,"(user// 4 2)"),Value::I64(2));
+	assert_eq!(eval_all(&environment,"(clojure.set/union #{1} #{2})").to_string(),"#{1 2}");
+	// clojure.set's fns live in clojure.set,  not in our namespace 
+	assert_eq!(eval_all(&environment,"union").type_tag().to_string(),"clojure.lang.Condition");
+	match eval_all(&environment,"set/union") {
+	    Value::Condition(condition) => assert_eq!(condition.message,"No such namespace: set"),
+	    value => panic!("Expected a condition, got: {}",value)
+	}
+	eval_all(&environment,"(alias (quote set) (quote clojure.set))");
+	assert_eq!(eval_all(&environment,"(set/intersection #{1 2} #{2})").to_string(),"#{2}");
+	match eval_all(&environment,"set/onion") {
+	    Value::Condition(condition) => assert_eq!(condition.message,"Undefined symbol set/onion"),
+	    value => panic!("Expected a condition, got: {}",value)
+	}
+    }
+    #[test]
+    fn test_data_readers()
+    {
+	let environment = core_environment();
+	assert_eq!(eval_all(&environment,"(inst-ms #inst \"1970-01-01T00:00:01Z\")"),Value::I64(1000));
+	assert_eq!(eval_all(&environment,"(= #uuid \"F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6\" #uuid \"f81d4fae-7dec-11d0-a765-00a0c91e6bf6\")"),Value::Boolean(true));
+	assert_eq!(eval_all(&environment,"(str #uuid \"f81d4fae-7dec-11d0-a765-00a0c91e6bf6\")"),Value::String(String::from("f81d4fae-7dec-11d0-a765-00a0c91e6bf6")));
+	// Our own data readers,  read from the form after they're defined on 
+	eval_all(&environment,"(def *data-readers* {'my/point (fn [coords] [:point (first coords) (nth coords 1)])})");
+	assert_eq!(eval_all(&environment,"#my/point (1 2)").to_string_explicit(),"[:point 1 2]");
+	eval_all(&environment,"(def *default-data-reader-fn* (fn [tag form] (str tag \" \" form)))");
+	assert_eq!(eval_all(&environment,"#unknown/tag 3"),Value::String(String::from("unknown/tag 3")));
+	// A data reader that fails fails our read,  its condition the cause 
+	eval_all(&environment,"(def *data-readers* {'my/point (fn [coords] (error :bad-point))})");
+	match reader::try_read(b"#my/point (1 2)") {
+	    Err(err) => {
+		let condition = err.to_condition();
+		assert_eq!(condition.message,"Reader Error: Invalid tagged literal: #my/point (1 2)");
+		assert_eq!(condition.cause.unwrap().condition_type,Keyword::intern("bad-point").to_rc_value());
+	    },
+	    Ok((_,form)) => panic!("Expected a reader error, got: {}",form)
+	}
+    }
+    #[test]
+    fn test_load_cljc()
+    {
+	let environment = core_environment();
+	let path = std::env::temp_dir().join(format!("shared_{}.cljc",std::process::id()));
+	std::fs::write(&path,"(def platform #?(:clj :jvm :rs :rust))\n(def nums [0 #?@(:clj [1] :rs [2 3])])\n").unwrap();
+	let loaded = repl::try_eval_file(&environment,path.to_str().unwrap());
+	std::fs::remove_file(&path).unwrap();
+	loaded.unwrap();
+	assert_eq!(eval_all(&environment,"platform").to_string(),":rust");
+	assert_eq!(eval_all(&environment,"nums").to_string(),"[0 2 3]");
+    }
+    #[test]
+    fn test_read_string()
+    {
+	let environment = core_environment();
+	assert_eq!(eval_all(&environment,"(read-string \"(+ 1 2)\")"),eval_all(&environment,"(quote (+ 1 2))"));
+	assert_eq!(eval_all(&environment,"(eval (read-string \"(+ 1 2)\"))"),Value::I64(3));
+	assert_eq!(eval_all(&environment,"(try (read-string \"(1 2]\") (catch :reader-error e (ex-data e)))").to_string(),
+		   "{:file \"NO_SOURCE_FILE\", :line 1, :column 5, :kind :unbalanced-delimiter}");
+	assert_eq!(eval_all(&environment,"(try (read-string \"\") (catch Exception e (ex-message e)))"),
+		   Value::String(std::string::String::from("Reader Error: EOF while reading")));
+    }
+    #[test]
+    fn test_source_positions_in_reports()
+    {
+	let environment = core_environment();
+	let source = Source::new("report.clj","(defn f [x]\n  (+ x :a))\n(f 1)");
+	let mut rest_input = source.text.as_bytes();
+	let mut last_value = Value::Nil;
+	while let (_rest_input,Some(form)) = reader::try_read_source(&source,rest_input).unwrap() {
+	    last_value = form.eval(Rc::clone(&environment));
+	    rest_input = _rest_input;
+	}
+	match last_value {
+	    Value::Condition(condition) => assert_eq!(condition.report().lines().skip(1).collect::<Vec<&str>>(),
+						      vec!["  at + report.clj:2:3",
+							   "        (+ x :a))",
+							   "        ^",
+							   "  at f report.clj:3:1"]),
+	    value => panic!("Expected a condition, got: {}",value)
+	}
+    }
+}